@@ -0,0 +1,382 @@
+use fontdue::Font;
+use tiny_skia::{
+    Color, FillRule, LineCap, LineJoin, Paint, Path, PathBuilder, Pixmap, Point, Rect, Stroke,
+    Transform,
+};
+
+use crate::render::{draw_glyphs, fill_rect, image_transform, text_width};
+use crate::theme::Theme;
+
+/// Colors marks are drawn in, picked in turn
+const COLORS: [(&str, (u8, u8, u8)); 6] = [
+    ("Red", (220, 40, 40)),
+    ("Orange", (240, 140, 20)),
+    ("Yellow", (250, 220, 30)),
+    ("Green", (40, 170, 60)),
+    ("Blue", (40, 110, 230)),
+    ("Black", (20, 20, 20)),
+];
+
+/// Sizes of the marks on the screen, they are kept in image pixels
+const PEN_WIDTH: f32 = 3.0;
+const HIGHLIGHTER_WIDTH: f32 = 16.0;
+const TEXT_SIZE: f32 = 18.0;
+const ARROW_HEAD: f32 = 5.0;
+const FONT_SIZE: f32 = 12.0;
+const LABEL_HEIGHT: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tool {
+    #[default]
+    Pen,
+    Arrow,
+    Rectangle,
+    Highlighter,
+    Text,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 5] = [
+        Tool::Pen,
+        Tool::Arrow,
+        Tool::Rectangle,
+        Tool::Highlighter,
+        Tool::Text,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Pen => "Pen",
+            Tool::Arrow => "Arrow",
+            Tool::Rectangle => "Rectangle",
+            Tool::Highlighter => "Highlighter",
+            Tool::Text => "Text",
+        }
+    }
+
+    pub fn next(self) -> Tool {
+        let index = Tool::ALL.iter().position(|t| *t == self).unwrap();
+        Tool::ALL[(index + 1) % Tool::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Pen(Vec<Point>),
+    Highlighter(Vec<Point>),
+    Arrow(Point, Point),
+    Rectangle(Point, Point),
+    /// Top left corner of the label and its text
+    Text(Point, String),
+}
+
+/// Something drawn over an image note, in image pixels so it scales with the note
+#[derive(Debug, Clone)]
+pub struct Mark {
+    shape: Shape,
+    color: Color,
+    /// Stroke width, or font size of a label
+    size: f32,
+}
+
+/// Drawing over an image note
+#[derive(Debug, Clone, Default)]
+pub struct Annotate {
+    pub tool: Tool,
+    color: usize,
+    /// Mark being drawn or typed, added to the note once it's done
+    current: Option<Mark>,
+}
+
+impl Annotate {
+    pub fn color_name(&self) -> &'static str {
+        COLORS[self.color].0
+    }
+
+    pub fn next_color(&mut self) {
+        self.color = (self.color + 1) % COLORS.len();
+    }
+
+    /// Picks a tool by its first letter, `C` changes the color
+    pub fn key(&mut self, key: &str) {
+        let key = key.to_lowercase();
+        if key == "c" {
+            return self.next_color();
+        }
+        if let Some(tool) = Tool::ALL
+            .into_iter()
+            .find(|tool| tool.name()[..1].eq_ignore_ascii_case(&key))
+        {
+            self.tool = tool;
+        }
+    }
+
+    /// Whether a label is being typed, keys go to it then
+    pub fn typing(&self) -> bool {
+        matches!(
+            self.current,
+            Some(Mark {
+                shape: Shape::Text(..),
+                ..
+            })
+        )
+    }
+
+    /// Starts a mark at the image point, `zoom` is how big image pixels are on the screen
+    pub fn start(&mut self, point: Point, zoom: f32) {
+        let (_, (r, g, b)) = COLORS[self.color];
+        let (shape, size) = match self.tool {
+            Tool::Pen => (Shape::Pen(vec![point]), PEN_WIDTH),
+            Tool::Highlighter => (Shape::Highlighter(vec![point]), HIGHLIGHTER_WIDTH),
+            Tool::Arrow => (Shape::Arrow(point, point), PEN_WIDTH),
+            Tool::Rectangle => (Shape::Rectangle(point, point), PEN_WIDTH),
+            Tool::Text => (Shape::Text(point, String::new()), TEXT_SIZE),
+        };
+        self.current = Some(Mark {
+            shape,
+            color: Color::from_rgba8(r, g, b, 255),
+            size: size / zoom,
+        });
+    }
+
+    pub fn drag_to(&mut self, point: Point) {
+        let Some(mark) = &mut self.current else {
+            return;
+        };
+        match &mut mark.shape {
+            Shape::Pen(points) | Shape::Highlighter(points) => points.push(point),
+            Shape::Arrow(_, end) | Shape::Rectangle(_, end) => *end = point,
+            Shape::Text(..) => {}
+        }
+    }
+
+    /// Whether a mark is being dragged out, labels are typed instead
+    pub fn dragging(&self) -> bool {
+        self.current.is_some() && !self.typing()
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        if let Some(Mark {
+            shape: Shape::Text(_, label),
+            ..
+        }) = &mut self.current
+        {
+            label.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let Some(Mark {
+            shape: Shape::Text(_, label),
+            ..
+        }) = &mut self.current
+        {
+            label.pop();
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.current = None;
+    }
+
+    /// The mark that was drawn or typed, `None` when there is nothing to keep
+    pub fn finish(&mut self) -> Option<Mark> {
+        let mark = self.current.take()?;
+        let empty = match &mark.shape {
+            Shape::Pen(points) | Shape::Highlighter(points) => points.len() < 2,
+            Shape::Arrow(start, end) | Shape::Rectangle(start, end) => start == end,
+            Shape::Text(_, label) => label.trim().is_empty(),
+        };
+        (!empty).then_some(mark)
+    }
+
+    /// The mark being drawn, with the tool and the color under the image
+    pub fn draw(
+        &self,
+        pixmap: &mut Pixmap,
+        area: Rect,
+        (width, height): (u32, u32),
+        font: &Font,
+        theme: &Theme,
+    ) {
+        let transform = image_transform(area, width, height);
+        if let Some(mark) = &self.current {
+            mark.draw(pixmap, transform, font);
+
+            // a caret after the label being typed
+            if let Shape::Text(corner, label) = &mark.shape {
+                let size = mark.size * transform.sy;
+                let mut corner = *corner;
+                transform.map_points(std::slice::from_mut(&mut corner));
+                let x = corner.x + text_width(label, size, font) + 1.0;
+                if let Some(caret) = Rect::from_xywh(x, corner.y, 1.5, size * 1.2) {
+                    fill_rect(pixmap, caret, mark.color);
+                }
+            }
+        }
+
+        let (_, (r, g, b)) = COLORS[self.color];
+        let label = format!("{} · {}", self.tool.name(), self.color_name());
+        let label_width = text_width(&label, FONT_SIZE, font) + LABEL_HEIGHT + 8.0;
+        let (x, y) = (area.left() + 4.0, area.bottom() - LABEL_HEIGHT - 4.0);
+        if let Some(rect) = Rect::from_xywh(x, y, label_width, LABEL_HEIGHT) {
+            fill_rect(pixmap, rect, theme.background);
+        }
+        if let Some(dot) = Rect::from_xywh(x + 5.0, y + 5.0, 10.0, 10.0) {
+            fill_rect(pixmap, dot, Color::from_rgba8(r, g, b, 255));
+        }
+        draw_glyphs(
+            pixmap,
+            &label,
+            x + LABEL_HEIGHT,
+            y + 14.0,
+            FONT_SIZE,
+            font,
+            theme.text,
+            false,
+            false,
+        );
+    }
+}
+
+impl Mark {
+    /// Moves the mark along with the image pixels, when the image is cropped, rotated or flipped
+    pub fn map(&mut self, transform: Transform, font: &Font) {
+        match &mut self.shape {
+            Shape::Pen(points) | Shape::Highlighter(points) => transform.map_points(points),
+            Shape::Arrow(start, end) | Shape::Rectangle(start, end) => {
+                for point in [start, end] {
+                    transform.map_points(std::slice::from_mut(point));
+                }
+            }
+            Shape::Text(corner, label) => {
+                // the label stays upright, so it keeps its middle on the marked spot
+                let (width, height) = (text_width(label, self.size, font), self.size * 1.2);
+                let mut middle = Point::from_xy(corner.x + width / 2.0, corner.y + height / 2.0);
+                transform.map_points(std::slice::from_mut(&mut middle));
+                *corner = Point::from_xy(middle.x - width / 2.0, middle.y - height / 2.0);
+            }
+        }
+    }
+
+    /// Draws the mark with the transform from image pixels to the pixmap
+    fn draw(&self, pixmap: &mut Pixmap, transform: Transform, font: &Font) {
+        let mut paint = Paint::default();
+        paint.set_color(self.color);
+        paint.anti_alias = true;
+        let stroke = Stroke {
+            width: self.size,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Stroke::default()
+        };
+
+        match &self.shape {
+            Shape::Pen(points) => {
+                if let Some(path) = polyline(points) {
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Shape::Highlighter(points) => {
+                // one path, so the parts that cross don't get darker
+                let mut color = self.color;
+                color.apply_opacity(0.4);
+                paint.set_color(color);
+                let stroke = Stroke {
+                    line_cap: LineCap::Butt,
+                    ..stroke
+                };
+                if let Some(path) = polyline(points) {
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Shape::Arrow(start, end) => {
+                let (dx, dy) = (end.x - start.x, end.y - start.y);
+                let length = dx.hypot(dy);
+                if length == 0.0 {
+                    return;
+                }
+                let head = (self.size * ARROW_HEAD).min(length);
+                let (ux, uy) = (dx / length, dy / length);
+                // the shaft stops at the head, so its round cap doesn't poke out of the tip
+                let base = Point::from_xy(end.x - ux * head, end.y - uy * head);
+
+                if let Some(path) = polyline(&[*start, base]) {
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+                let mut path = PathBuilder::new();
+                path.move_to(end.x, end.y);
+                path.line_to(base.x - uy * head * 0.6, base.y + ux * head * 0.6);
+                path.line_to(base.x + uy * head * 0.6, base.y - ux * head * 0.6);
+                path.close();
+                if let Some(path) = path.finish() {
+                    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+                }
+            }
+            Shape::Rectangle(start, end) => {
+                let rect = Rect::from_ltrb(
+                    start.x.min(end.x),
+                    start.y.min(end.y),
+                    start.x.max(end.x),
+                    start.y.max(end.y),
+                );
+                if let Some(path) = rect.map(PathBuilder::from_rect) {
+                    let stroke = Stroke {
+                        line_join: LineJoin::Miter,
+                        ..stroke
+                    };
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Shape::Text(corner, label) => {
+                let size = self.size * transform.sy;
+                let mut corner = *corner;
+                transform.map_points(std::slice::from_mut(&mut corner));
+                draw_glyphs(
+                    pixmap,
+                    label,
+                    corner.x,
+                    corner.y + size,
+                    size,
+                    font,
+                    self.color,
+                    true,
+                    false,
+                );
+            }
+        }
+    }
+}
+
+fn polyline(points: &[Point]) -> Option<Path> {
+    let (first, rest) = points.split_first()?;
+    let mut path = PathBuilder::new();
+    path.move_to(first.x, first.y);
+    for point in rest {
+        path.line_to(point.x, point.y);
+    }
+    path.finish()
+}
+
+/// Draws the marks over the image note, stretched like the image is
+pub fn draw_marks(
+    pixmap: &mut Pixmap,
+    area: Rect,
+    (width, height): (u32, u32),
+    marks: &[Mark],
+    font: &Font,
+) {
+    let transform = image_transform(area, width, height);
+    for mark in marks {
+        mark.draw(pixmap, transform, font);
+    }
+}
+
+/// The image with the marks drawn into it, as it's copied and exported
+pub fn bake(image: &Pixmap, marks: &[Mark], font: &Font) -> Pixmap {
+    let mut image = image.clone();
+    for mark in marks {
+        mark.draw(&mut image, Transform::identity(), font);
+    }
+    image
+}
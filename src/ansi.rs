@@ -0,0 +1,326 @@
+use tiny_skia::Color;
+
+use crate::render::expand_tabs;
+use crate::rich::{Line, Span, Style};
+
+const ESCAPE: char = '\x1b';
+
+/// The 16 basic terminal colors, normal then bright
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 49, 49),
+    (13, 188, 121),
+    (229, 229, 16),
+    (36, 114, 200),
+    (188, 63, 188),
+    (17, 168, 205),
+    (229, 229, 229),
+    (102, 102, 102),
+    (241, 76, 76),
+    (35, 209, 139),
+    (245, 245, 67),
+    (59, 142, 234),
+    (214, 112, 214),
+    (41, 184, 219),
+    (255, 255, 255),
+];
+
+pub fn has_escapes(text: &str) -> bool {
+    text.contains(ESCAPE)
+}
+
+/// A piece of text between escape sequences, or the parameters of an SGR sequence
+enum Part<'a> {
+    Text(&'a str),
+    Sgr(&'a str),
+}
+
+/// Splits the text at escape sequences, everything but SGR ones is dropped
+fn parts(text: &str) -> Vec<Part<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(ESCAPE) {
+        if start > 0 {
+            parts.push(Part::Text(&rest[..start]));
+        }
+        let sequence = &rest[start + 1..];
+
+        let end = match sequence.chars().next() {
+            // CSI: parameters, then a final byte from `@` to `~`
+            Some('[') => {
+                let end = sequence[1..]
+                    .find(|c: char| ('@'..='~').contains(&c))
+                    .map_or(sequence.len(), |i| i + 2);
+                if sequence[..end].ends_with('m') {
+                    parts.push(Part::Sgr(&sequence[1..end - 1]));
+                }
+                end
+            }
+            // OSC, like window titles and links: ends with BEL or `ESC \`
+            Some(']') => match (sequence.find('\x07'), sequence.find("\x1b\\")) {
+                (Some(bell), Some(st)) if st < bell => st + 2,
+                (Some(bell), _) => bell + 1,
+                (None, Some(st)) => st + 2,
+                (None, None) => sequence.len(),
+            },
+            Some(c) => c.len_utf8(),
+            None => 0,
+        };
+        rest = &sequence[end..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+
+    parts
+}
+
+/// The text without escape sequences, with lines a carriage return redrew as they were left
+pub fn strip(text: &str) -> String {
+    let text: String = parts(text)
+        .into_iter()
+        .filter_map(|part| match part {
+            Part::Text(text) => Some(text),
+            Part::Sgr(_) => None,
+        })
+        .collect();
+
+    text.split('\n')
+        .map(|line| overwrite(line, true, &mut Vec::new()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What is left of a piece of a line after carriage returns redraw it, like progress bars do.
+/// A carriage return clears the spans already on the line, one before a newline is kept out
+fn overwrite<'a>(piece: &'a str, ends_line: bool, line: &mut Vec<Span>) -> &'a str {
+    let piece = if ends_line {
+        piece.strip_suffix('\r').unwrap_or(piece)
+    } else {
+        piece
+    };
+    match piece.rfind('\r') {
+        Some(i) => {
+            line.clear();
+            &piece[i + 1..]
+        }
+        None => piece,
+    }
+}
+
+/// Styled lines of terminal output, in a monospace font like the terminal had
+pub fn parse(text: &str) -> Vec<Line> {
+    let mut state = Sgr::default();
+    let mut style = state.style();
+    let mut lines = vec![Vec::new()];
+
+    for part in parts(text) {
+        match part {
+            Part::Sgr(params) => {
+                state.apply(params);
+                style = state.style();
+            }
+            Part::Text(text) => {
+                let pieces: Vec<&str> = text.split('\n').collect();
+                for (i, piece) in pieces.iter().enumerate() {
+                    if i > 0 {
+                        lines.push(Vec::new());
+                    }
+                    let line = lines.last_mut().unwrap();
+                    let piece = overwrite(piece, i + 1 < pieces.len(), line);
+                    if !piece.is_empty() {
+                        line.push(Span::new(piece, style));
+                    }
+                }
+            }
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|spans| Line {
+            spans: expand_spans(spans),
+            wrap: false,
+            ..Line::default()
+        })
+        .collect()
+}
+
+/// Expands tabs across the spans of a line, so columns line up as in the terminal
+fn expand_spans(spans: Vec<Span>) -> Vec<Span> {
+    let mut column = 0;
+    spans
+        .into_iter()
+        .map(|span| {
+            let padded = format!("{}{}", " ".repeat(column % 8), span.text);
+            let text = expand_tabs(&padded, 8)[column % 8..].to_string();
+            column += text.chars().count();
+            Span::new(text, span.style)
+        })
+        .collect()
+}
+
+/// Graphic rendition the sequences set so far
+#[derive(Debug, Clone, Copy, Default)]
+struct Sgr {
+    foreground: Option<Color>,
+    background: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+    strike: bool,
+}
+
+impl Sgr {
+    fn apply(&mut self, params: &str) {
+        let codes: Vec<u32> = params
+            .split([';', ':'])
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+        let mut codes = codes.into_iter();
+
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Sgr::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                9 => self.strike = true,
+                21 => self.underline = true,
+                22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                29 => self.strike = false,
+                30..=37 => self.foreground = Some(basic(code - 30)),
+                38 => self.foreground = extended(&mut codes),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(basic(code - 40)),
+                48 => self.background = extended(&mut codes),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(basic(code - 90 + 8)),
+                100..=107 => self.background = Some(basic(code - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+
+    fn style(&self) -> Style {
+        let (mut color, background) = if self.inverse {
+            // colors that weren't set are the ones of a light terminal
+            (
+                Some(self.background.unwrap_or(Color::WHITE)),
+                Some(self.foreground.unwrap_or(Color::BLACK)),
+            )
+        } else {
+            (self.foreground, self.background)
+        };
+        if self.dim {
+            color = Some(
+                color.map_or(Color::from_rgba8(128, 128, 128, 255), |mut color| {
+                    color.apply_opacity(0.6);
+                    color
+                }),
+            );
+        }
+
+        Style {
+            color,
+            background,
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            strike: self.strike,
+            mono: true,
+        }
+    }
+}
+
+fn basic(index: u32) -> Color {
+    let (r, g, b) = PALETTE[index as usize];
+    Color::from_rgba8(r, g, b, 255)
+}
+
+/// `5;n` picks from the 256 color palette, `2;r;g;b` is a true color
+fn extended(codes: &mut impl Iterator<Item = u32>) -> Option<Color> {
+    let byte = |code: Option<u32>| code.unwrap_or(0).min(255) as u8;
+
+    match codes.next()? {
+        5 => {
+            let index = codes.next()?;
+            Some(match index {
+                0..=15 => basic(index),
+                16..=231 => {
+                    let cube = index - 16;
+                    let level = |n: u32| if n == 0 { 0 } else { (55 + n * 40) as u8 };
+                    Color::from_rgba8(level(cube / 36), level(cube / 6 % 6), level(cube % 6), 255)
+                }
+                _ => {
+                    let gray = (8 + (index.min(255) - 232) * 10) as u8;
+                    Color::from_rgba8(gray, gray, gray, 255)
+                }
+            })
+        }
+        2 => Some(Color::from_rgba8(
+            byte(codes.next()),
+            byte(codes.next()),
+            byte(codes.next()),
+            255,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(line: &Line) -> Vec<&str> {
+        line.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    #[test]
+    fn strips_escapes() {
+        assert_eq!(strip("\x1b[1;31mred\x1b[0m plain"), "red plain");
+        assert_eq!(strip("\x1b]0;title\x07text\x1b[2K"), "text");
+        assert_eq!(strip("\x1b]8;;https://a.b\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip("10%\r50%\r100%\ndone\r\n"), "100%\ndone\n");
+    }
+
+    #[test]
+    fn parses_colors_and_styles() {
+        let lines = parse("\x1b[1;32mok\x1b[0m \x1b[38;5;196mred\x1b[38;2;1;2;3mrgb\nnext");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(texts(&lines[0]), ["ok", " ", "red", "rgb"]);
+
+        let spans = &lines[0].spans;
+        assert!(spans[0].style.bold);
+        assert_eq!(spans[0].style.color, Some(basic(2)));
+        assert!(!spans[1].style.bold && spans[1].style.color.is_none());
+        assert_eq!(
+            spans[2].style.color,
+            Some(Color::from_rgba8(255, 0, 0, 255))
+        );
+        assert_eq!(spans[3].style.color, Some(Color::from_rgba8(1, 2, 3, 255)));
+        // styles carry over to the next line
+        assert_eq!(lines[1].spans[0].style.color, spans[3].style.color);
+    }
+
+    #[test]
+    fn carriage_returns_redraw_the_line() {
+        let lines = parse("\x1b[33m10%\r\x1b[32m100%\n");
+        assert_eq!(texts(&lines[0]), ["100%"]);
+        assert_eq!(lines[0].spans[0].style.color, Some(basic(2)));
+    }
+
+    #[test]
+    fn expands_tabs_across_spans() {
+        let lines = parse("ab\x1b[1m\tc");
+        assert_eq!(texts(&lines[0]), ["ab", "      c"]);
+    }
+}
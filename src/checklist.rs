@@ -0,0 +1,239 @@
+use fontdue::Font;
+use tiny_skia::{Color, LineCap, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Stroke, Transform};
+
+use crate::render::{fill_rect, render_text, text_width};
+use crate::theme::Theme;
+
+const PADDING: f32 = 10.0;
+const MAX_FONT_SIZE: f32 = 40.0;
+
+#[derive(Debug, Clone)]
+pub struct Item {
+    pub text: String,
+    pub checked: bool,
+}
+
+/// Text shown as a list of items that can be checked off
+#[derive(Debug, Clone)]
+pub struct Checklist {
+    pub items: Vec<Item>,
+}
+
+impl Checklist {
+    /// Every non-empty line is an item, `- [ ]` and `- [x]` markers set the state
+    pub fn parse(text: &str) -> Self {
+        let items = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let line = ["- ", "* ", "+ ", "• "]
+                    .iter()
+                    .find_map(|bullet| line.strip_prefix(bullet))
+                    .unwrap_or(line);
+
+                match task_marker(line) {
+                    Some((checked, text)) => Item {
+                        text: text.to_string(),
+                        checked,
+                    },
+                    None => Item {
+                        text: line.to_string(),
+                        checked: false,
+                    },
+                }
+            })
+            .collect();
+
+        Checklist { items }
+    }
+
+    /// Whether the text is written as a task list, so it's shown as one right away
+    pub fn is_task_list(text: &str) -> bool {
+        text.lines().any(|line| {
+            let line = line.trim();
+            ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|bullet| line.strip_prefix(bullet))
+                .and_then(task_marker)
+                .is_some()
+        })
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.checked = !item.checked;
+        }
+    }
+
+    /// Checked items and all items
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.items.iter().filter(|item| item.checked).count();
+        (done, self.items.len())
+    }
+
+    /// The list as a markdown task list, with the checked state
+    pub fn to_text(&self) -> String {
+        self.items
+            .iter()
+            .map(|item| {
+                let mark = if item.checked { 'x' } else { ' ' };
+                format!("- [{mark}] {}", item.text)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn font_size(&self, area: Rect, font: &Font) -> f32 {
+        let longest = self
+            .items
+            .iter()
+            .map(|item| text_width(&item.text, 1.0, font))
+            .fold(0.0, f32::max)
+            .max(1.0);
+
+        // a row is 1.5 times the text, the box takes one more text height
+        let by_height = (area.height() - PADDING * 2.0) / self.items.len().max(1) as f32 / 1.5;
+        let by_width = (area.width() - PADDING * 2.0) / (longest + 1.5);
+        by_height.min(by_width).clamp(1.0, MAX_FONT_SIZE)
+    }
+
+    /// Row of every item in the content area
+    pub fn layout(&self, area: Rect, font: &Font) -> Vec<Rect> {
+        let size = self.font_size(area, font);
+        let row_height = size * 1.5;
+
+        (0..self.items.len())
+            .filter_map(|i| {
+                Rect::from_xywh(
+                    area.x() + PADDING,
+                    area.y() + PADDING + row_height * i as f32,
+                    area.width() - PADDING * 2.0,
+                    row_height,
+                )
+            })
+            .collect()
+    }
+
+    pub fn draw(&self, pixmap: &mut Pixmap, area: Rect, font: &Font, theme: &Theme) {
+        let size = self.font_size(area, font);
+
+        for (item, row) in self.items.iter().zip(self.layout(area, font)) {
+            let box_size = size * 0.8;
+            let box_rect = Rect::from_xywh(
+                row.x(),
+                row.y() + (row.height() - box_size) / 2.0,
+                box_size,
+                box_size,
+            )
+            .unwrap();
+            draw_checkbox(pixmap, box_rect, item.checked, theme.text);
+
+            let mut color = theme.text;
+            if item.checked {
+                color.apply_opacity(0.5);
+            }
+            let text = render_text(item.text.clone(), size, font, color);
+            let x = row.x() + size * 1.5;
+            let y = row.y() + (row.height() - text.height() as f32) / 2.0;
+            pixmap.draw_pixmap(
+                x as i32,
+                y as i32,
+                text.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+
+            if item.checked {
+                let strike = Rect::from_xywh(
+                    x,
+                    row.y() + row.height() / 2.0,
+                    text.width() as f32,
+                    (size / 12.0).max(1.0),
+                );
+                if let Some(strike) = strike {
+                    fill_rect(pixmap, strike, color);
+                }
+            }
+        }
+    }
+}
+
+/// Splits `[ ] text` or `[x] text` into the state and the text
+fn task_marker(line: &str) -> Option<(bool, &str)> {
+    let checked = match line.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((checked, line[3..].trim_start()))
+}
+
+fn draw_checkbox(pixmap: &mut Pixmap, rect: Rect, checked: bool, color: Color) {
+    let mut paint = Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = true;
+    let stroke = Stroke {
+        width: (rect.width() / 10.0).max(1.0),
+        line_cap: LineCap::Round,
+        ..Stroke::default()
+    };
+
+    let outline = PathBuilder::from_rect(rect);
+    pixmap.stroke_path(&outline, &paint, &stroke, Transform::identity(), None);
+
+    if checked {
+        let (x, y, w) = (rect.x(), rect.y(), rect.width());
+        let mut path = PathBuilder::new();
+        path.move_to(x + w * 0.2, y + w * 0.5);
+        path.line_to(x + w * 0.42, y + w * 0.75);
+        path.line_to(x + w * 0.8, y + w * 0.25);
+        if let Some(path) = path.finish() {
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_items() {
+        let checklist = Checklist::parse("- [ ] milk\n* [x] eggs\n\n  bread  \n+ [X] jam\n[ ]");
+        let items: Vec<(&str, bool)> = checklist
+            .items
+            .iter()
+            .map(|item| (item.text.as_str(), item.checked))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("milk", false),
+                ("eggs", true),
+                ("bread", false),
+                ("jam", true),
+                ("", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn toggles_and_writes_back() {
+        let mut checklist = Checklist::parse("milk\n- [x] eggs");
+        checklist.toggle(0);
+        checklist.toggle(1);
+        checklist.toggle(5);
+        assert_eq!(checklist.progress(), (1, 2));
+        assert_eq!(checklist.to_text(), "- [x] milk\n- [ ] eggs");
+    }
+
+    #[test]
+    fn detects_task_lists() {
+        assert!(Checklist::is_task_list("notes\n- [ ] milk"));
+        assert!(Checklist::is_task_list("  * [x] done"));
+        assert!(!Checklist::is_task_list("- milk\n- eggs"));
+        assert!(!Checklist::is_task_list("[ ] no bullet"));
+    }
+}
@@ -0,0 +1,64 @@
+use std::env;
+use std::process;
+use std::time::Duration;
+
+use crate::code::Language;
+use crate::expiry::MAX_TTL;
+
+const USAGE: &str = "Usage: fonotes [OPTIONS]
+
+Options:
+  --ttl <SECONDS>  Close every note by itself after this many seconds
+  --lang <NAME>    Highlight text notes as rust, python, js, sh, json, yaml or sql
+  --reminders      List the pending reminders and exit
+  --stdin          Open a note with the text piped in, like `ls --color | fonotes --stdin`
+  -h, --help       Print this help";
+
+/// Command line options, they override the config file
+#[derive(Debug, Default)]
+pub struct Args {
+    pub ttl: Option<Duration>,
+    pub reminders: bool,
+    pub language: Option<Language>,
+    pub stdin: bool,
+}
+
+impl Args {
+    /// Parses the process arguments, exits with usage on anything unknown
+    pub fn parse() -> Self {
+        let mut args = Args::default();
+        let mut iter = env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--ttl" => {
+                    let secs = iter.next().and_then(|value| value.parse().ok());
+                    match secs {
+                        Some(secs) if secs > 0 && secs <= MAX_TTL.as_secs() => {
+                            args.ttl = Some(Duration::from_secs(secs))
+                        }
+                        _ => fail("--ttl needs a number of seconds, at most a year"),
+                    }
+                }
+                "--lang" => match iter.next().as_deref().and_then(Language::by_name) {
+                    Some(language) => args.language = Some(language),
+                    None => fail("--lang needs one of rust, python, js, sh, json, yaml, sql"),
+                },
+                "--reminders" => args.reminders = true,
+                "--stdin" => args.stdin = true,
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    process::exit(0);
+                }
+                _ => fail(&format!("unknown argument `{arg}`")),
+            }
+        }
+
+        args
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("fonotes: {message}\n\n{USAGE}");
+    process::exit(2);
+}
@@ -0,0 +1,579 @@
+use tiny_skia::Color;
+
+use crate::render::expand_tabs;
+use crate::rich::{Line, Span, Style};
+use crate::theme::{mix, Theme};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Shell,
+    Json,
+    Yaml,
+    Sql,
+}
+
+impl Language {
+    pub const ALL: [Language; 7] = [
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::Shell,
+        Language::Json,
+        Language::Yaml,
+        Language::Sql,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::JavaScript => "JavaScript",
+            Language::Shell => "Shell",
+            Language::Json => "JSON",
+            Language::Yaml => "YAML",
+            Language::Sql => "SQL",
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Language> {
+        match name.to_lowercase().as_str() {
+            "rust" | "rs" => Some(Language::Rust),
+            "python" | "py" => Some(Language::Python),
+            "javascript" | "js" | "typescript" | "ts" => Some(Language::JavaScript),
+            "shell" | "sh" | "bash" => Some(Language::Shell),
+            "json" => Some(Language::Json),
+            "yaml" | "yml" => Some(Language::Yaml),
+            "sql" => Some(Language::Sql),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Language::ALL.iter().position(|l| *l == self).unwrap();
+        Language::ALL[(index + 1) % Language::ALL.len()]
+    }
+
+    /// Guesses the language from telltale bits, `None` for prose
+    pub fn detect(text: &str) -> Option<Language> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        if (trimmed.starts_with('{') && trimmed.ends_with('}'))
+            || (trimmed.starts_with('[') && trimmed.ends_with(']') && trimmed.contains('"'))
+        {
+            return Some(Language::Json);
+        }
+        if trimmed.starts_with("#!") {
+            return Some(if trimmed.lines().next().unwrap().contains("python") {
+                Language::Python
+            } else {
+                Language::Shell
+            });
+        }
+
+        let lower = trimmed.to_lowercase();
+        let scores = [
+            (
+                Language::Rust,
+                code_score(
+                    &[
+                        "fn ", "let mut ", "impl ", "pub ", "::", "-> ", "#[", "&self", "println!",
+                        "match ",
+                    ],
+                    trimmed,
+                ),
+            ),
+            (
+                Language::Python,
+                code_score(
+                    &[
+                        "def ", "import ", "self.", "elif ", "print(", "__init__", "None", "):\n",
+                        "lambda ",
+                    ],
+                    trimmed,
+                ),
+            ),
+            (
+                Language::JavaScript,
+                code_score(
+                    &[
+                        "function",
+                        "const ",
+                        "=> ",
+                        "console.",
+                        "require(",
+                        "export ",
+                        "===",
+                        "undefined",
+                        "document.",
+                    ],
+                    trimmed,
+                ),
+            ),
+            (
+                Language::Shell,
+                code_score(
+                    &[
+                        "$ ", "sudo ", "echo ", "fi\n", "then", " | ", "&& ", "export ", "apt ",
+                        "cd ", "--",
+                    ],
+                    trimmed,
+                ),
+            ),
+            (
+                Language::Sql,
+                code_score(
+                    &[
+                        "select ",
+                        " from ",
+                        "where ",
+                        "insert into",
+                        "update ",
+                        "create table",
+                        "join ",
+                        "group by",
+                        "order by",
+                    ],
+                    &lower,
+                ),
+            ),
+            (Language::Yaml, yaml_score(trimmed)),
+        ];
+
+        scores
+            .into_iter()
+            .filter(|(_, score)| *score >= 2)
+            .max_by_key(|(_, score)| *score)
+            .map(|(language, _)| language)
+    }
+
+    fn syntax(self) -> Syntax {
+        match self {
+            Language::Rust => Syntax {
+                keywords: &[
+                    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+                    "enum", "extern", "fn", "for", "if", "impl", "in", "let", "loop", "match",
+                    "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+                    "struct", "super", "trait", "type", "unsafe", "use", "where", "while", "true",
+                    "false",
+                ],
+                line_comment: &["//"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"'],
+                ..Syntax::default()
+            },
+            Language::Python => Syntax {
+                keywords: &[
+                    "and", "as", "assert", "async", "await", "break", "class", "continue", "def",
+                    "del", "elif", "else", "except", "finally", "for", "from", "global", "if",
+                    "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise",
+                    "return", "try", "while", "with", "yield", "None", "True", "False", "self",
+                ],
+                line_comment: &["#"],
+                quotes: &['"', '\''],
+                ..Syntax::default()
+            },
+            Language::JavaScript => Syntax {
+                keywords: &[
+                    "async",
+                    "await",
+                    "break",
+                    "case",
+                    "catch",
+                    "class",
+                    "const",
+                    "continue",
+                    "default",
+                    "delete",
+                    "do",
+                    "else",
+                    "export",
+                    "extends",
+                    "finally",
+                    "for",
+                    "from",
+                    "function",
+                    "if",
+                    "import",
+                    "in",
+                    "instanceof",
+                    "let",
+                    "new",
+                    "of",
+                    "return",
+                    "switch",
+                    "this",
+                    "throw",
+                    "try",
+                    "typeof",
+                    "var",
+                    "void",
+                    "while",
+                    "yield",
+                    "true",
+                    "false",
+                    "null",
+                    "undefined",
+                ],
+                line_comment: &["//"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['"', '\'', '`'],
+                ..Syntax::default()
+            },
+            Language::Shell => Syntax {
+                keywords: &[
+                    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done",
+                    "case", "esac", "in", "function", "return", "export", "local", "sudo", "echo",
+                    "cd",
+                ],
+                line_comment: &["#"],
+                quotes: &['"', '\''],
+                variables: true,
+                ..Syntax::default()
+            },
+            Language::Json => Syntax {
+                keywords: &["true", "false", "null"],
+                quotes: &['"'],
+                keys: true,
+                ..Syntax::default()
+            },
+            Language::Yaml => Syntax {
+                keywords: &["true", "false", "null", "yes", "no", "on", "off"],
+                line_comment: &["#"],
+                quotes: &['"', '\''],
+                keys: true,
+                ..Syntax::default()
+            },
+            Language::Sql => Syntax {
+                keywords: &[
+                    "select", "from", "where", "and", "or", "not", "insert", "into", "values",
+                    "update", "set", "delete", "create", "table", "drop", "alter", "index", "join",
+                    "left", "right", "inner", "outer", "on", "as", "group", "by", "order",
+                    "having", "limit", "offset", "distinct", "null", "is", "in", "like", "primary",
+                    "key", "default", "union", "case", "when", "then", "else", "end", "asc",
+                    "desc", "count", "exists",
+                ],
+                line_comment: &["--"],
+                block_comment: Some(("/*", "*/")),
+                quotes: &['\'', '"'],
+                ignore_case: true,
+                ..Syntax::default()
+            },
+        }
+    }
+}
+
+/// Signals found in the text, `0` unless most lines look like code.
+/// Prose mentions a keyword here and there, code has them or its punctuation on most lines
+fn code_score(signals: &[&str], text: &str) -> usize {
+    let hits = signals.iter().filter(|s| text.contains(*s)).count();
+
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let code_lines = lines
+        .iter()
+        .filter(|line| {
+            let trimmed = line.trim_end();
+            let sentence =
+                trimmed.ends_with(['.', '!', '?']) && trimmed.split_whitespace().count() >= 6;
+            !sentence
+                && (line.starts_with([' ', '\t'])
+                    || trimmed.ends_with([';', '{', '}', '(', ')', '[', ']', ':', ','])
+                    || signals.iter().any(|s| line.contains(s)))
+        })
+        .count();
+
+    if code_lines * 2 >= lines.len() {
+        hits
+    } else {
+        0
+    }
+}
+
+/// Lines that look like `key: value` or `- item`
+fn yaml_score(text: &str) -> usize {
+    if text.contains(['{', ';', '(']) {
+        return 0;
+    }
+
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let yaml_lines = lines
+        .iter()
+        .filter(|line| {
+            let line = line.trim_start().trim_start_matches("- ");
+            line.split_once(':').is_some_and(|(key, rest)| {
+                !key.is_empty() && !key.contains(' ') && (rest.is_empty() || rest.starts_with(' '))
+            })
+        })
+        .count();
+
+    // every other line has to look like yaml, single `key: value` lines are just prose
+    if lines.len() >= 2 && yaml_lines * 2 >= lines.len() {
+        yaml_lines
+    } else {
+        0
+    }
+}
+
+#[derive(Default)]
+struct Syntax {
+    keywords: &'static [&'static str],
+    line_comment: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// `"key":` in JSON and `key:` in YAML
+    keys: bool,
+    /// `$NAME` and `${NAME}` in shell
+    variables: bool,
+    ignore_case: bool,
+}
+
+/// Kind of a piece of code, picks its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Plain,
+    Keyword,
+    Type,
+    Function,
+    String,
+    Number,
+    Comment,
+    Key,
+    Variable,
+}
+
+impl Token {
+    pub fn color(self, theme: &Theme) -> Option<Color> {
+        // fixed hues pulled a bit towards the text color, so they suit every theme
+        let hue = |r, g, b| Some(mix(Color::from_rgba8(r, g, b, 255), theme.text, 0.2));
+        match self {
+            Token::Plain => None,
+            Token::Keyword => hue(150, 60, 200),
+            Token::Type => hue(20, 140, 150),
+            Token::Function => hue(50, 100, 220),
+            Token::String => hue(40, 150, 60),
+            Token::Number => hue(210, 110, 20),
+            Token::Key => hue(50, 100, 220),
+            Token::Variable => hue(20, 140, 150),
+            Token::Comment => {
+                let mut color = theme.text;
+                color.apply_opacity(0.5);
+                Some(color)
+            }
+        }
+    }
+}
+
+/// Code as monospace lines with colored tokens, tabs expanded to 4 columns
+pub fn highlight(text: &str, language: Language, theme: &Theme) -> Vec<Line> {
+    let syntax = language.syntax();
+    let mut in_block = false;
+
+    text.lines()
+        .map(|line| {
+            let line = expand_tabs(line, 4);
+            let spans = tokenize(&line, &syntax, &mut in_block)
+                .into_iter()
+                .map(|(token, text)| {
+                    Span::new(
+                        text,
+                        Style {
+                            color: token.color(theme),
+                            italic: token == Token::Comment,
+                            mono: true,
+                            ..Style::default()
+                        },
+                    )
+                })
+                .collect();
+
+            Line {
+                spans,
+                wrap: false,
+                ..Line::default()
+            }
+        })
+        .collect()
+}
+
+fn tokenize(line: &str, syntax: &Syntax, in_block: &mut bool) -> Vec<(Token, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens: Vec<(Token, String)> = Vec::new();
+    let mut push = |token: Token, text: &[char]| match tokens.last_mut() {
+        Some((last, existing)) if *last == token => existing.extend(text),
+        _ => tokens.push((token, text.iter().collect())),
+    };
+    let starts_with = |i: usize, pattern: &str| {
+        let pattern: Vec<char> = pattern.chars().collect();
+        chars[i..].starts_with(&pattern)
+    };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || (syntax.keys && c == '-');
+
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if *in_block {
+            let (_, end) = syntax.block_comment.unwrap();
+            let close = (i..chars.len()).find(|j| starts_with(*j, end));
+            let stop = close.map_or(chars.len(), |j| j + end.chars().count());
+            push(Token::Comment, &chars[i..stop]);
+            *in_block = close.is_none();
+            i = stop;
+            continue;
+        }
+
+        if let Some((start, _)) = syntax.block_comment {
+            if starts_with(i, start) {
+                *in_block = true;
+                push(Token::Comment, &chars[i..i + start.chars().count()]);
+                i += start.chars().count();
+                continue;
+            }
+        }
+
+        // `#` only starts a comment at a word boundary, `$#` and `a#b` are not comments
+        if syntax
+            .line_comment
+            .iter()
+            .any(|start| starts_with(i, start))
+            && (c != '#' || i == 0 || chars[i - 1].is_whitespace())
+        {
+            push(Token::Comment, &chars[i..]);
+            break;
+        }
+
+        if syntax.quotes.contains(&c) {
+            let mut end = i + 1;
+            while end < chars.len() && chars[end] != c {
+                end += if chars[end] == '\\' { 2 } else { 1 };
+            }
+            let end = (end + 1).min(chars.len());
+
+            let rest = chars[end..].iter().find(|c| !c.is_whitespace());
+            let token = if syntax.keys && rest == Some(&':') {
+                Token::Key
+            } else {
+                Token::String
+            };
+            push(token, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if syntax.variables && c == '$' {
+            let end = if chars.get(i + 1) == Some(&'{') {
+                (i..chars.len())
+                    .find(|j| chars[*j] == '}')
+                    .map_or(chars.len(), |j| j + 1)
+            } else {
+                (i + 1..chars.len())
+                    .find(|j| !(chars[*j].is_alphanumeric() || chars[*j] == '_'))
+                    .unwrap_or(chars.len())
+                    .max(i + 1)
+            };
+            push(Token::Variable, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if c.is_ascii_digit() && (i == 0 || !is_word(chars[i - 1])) {
+            let end = (i..chars.len())
+                .find(|j| {
+                    !(chars[*j].is_ascii_alphanumeric() || chars[*j] == '.' || chars[*j] == '_')
+                })
+                .unwrap_or(chars.len());
+            push(Token::Number, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let end = (i..chars.len())
+                .find(|j| !is_word(chars[*j]))
+                .unwrap_or(chars.len());
+            let word: String = chars[i..end].iter().collect();
+            let next = chars[end..].iter().find(|c| !c.is_whitespace());
+            let line_start = chars[..i].iter().all(|c| c.is_whitespace() || *c == '-');
+
+            let keyword = if syntax.ignore_case {
+                syntax.keywords.contains(&word.to_lowercase().as_str())
+            } else {
+                syntax.keywords.contains(&word.as_str())
+            };
+
+            let token = if syntax.keys && line_start && next == Some(&':') {
+                Token::Key
+            } else if keyword {
+                Token::Keyword
+            } else if next == Some(&'(') || (next == Some(&'!') && !syntax.keys) {
+                Token::Function
+            } else if c.is_uppercase() && !syntax.ignore_case && !syntax.keys {
+                Token::Type
+            } else {
+                Token::Plain
+            };
+            push(token, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        push(Token::Plain, &chars[i..i + 1]);
+        i += 1;
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Language;
+
+    #[test]
+    fn detects_languages() {
+        let cases = [
+            (
+                "fn main() {\n    let mut x = 5;\n    println!(\"{x}\");\n}",
+                Language::Rust,
+            ),
+            (
+                "def foo(self):\n    return None\n\nimport os",
+                Language::Python,
+            ),
+            ("const x = () => 1;\nconsole.log(x)", Language::JavaScript),
+            ("cargo build && cargo test --workspace", Language::Shell),
+            ("SELECT * FROM users WHERE id = 1", Language::Sql),
+            ("{\"a\": 1}", Language::Json),
+            ("name: app\nversion: 1", Language::Yaml),
+            ("#!/usr/bin/env python3\nprint(1)", Language::Python),
+        ];
+        for (text, language) in cases {
+            assert_eq!(Language::detect(text), Some(language), "{text}");
+        }
+    }
+
+    #[test]
+    fn prose_is_not_code() {
+        let cases = [
+            "I will select the best one from the shop, then go home.\n\
+             Meet me where we met -- you know the place.\n\
+             See you then!",
+            "Then pick the option from the list where it says so and press OK.",
+            "Let me know what you think: the new plan looks fine to me.",
+            "",
+        ];
+        for text in cases {
+            assert_eq!(Language::detect(text), None, "{text}");
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::code::Language;
+use crate::expiry::MAX_TTL;
+use crate::placement::Placement;
+use crate::theme::Theme;
+
+/// Settings read from the config file, every missing key keeps its default
+///
+/// The file is a list of `key = value` lines, `#` starts a comment
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub theme: Theme,
+    pub title_bar: bool,
+    pub export_dir: PathBuf,
+    pub snap_distance: i32,
+    pub placement: Placement,
+    /// Text size new notes are made to fit, the text is scaled with the note after that
+    pub font_size: f32,
+    /// Lifetime of temporary notes
+    pub ttl: Duration,
+    /// How long Ctrl+R hides a note for
+    pub snooze: Duration,
+    /// Length of a countdown when the clipboard doesn't hold one
+    pub countdown: Duration,
+    /// Language every text note is highlighted as, detected per note when `None`
+    pub language: Option<Language>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            theme: Theme::default(),
+            title_bar: true,
+            export_dir: home_dir().unwrap_or_else(|| PathBuf::from(".")),
+            snap_distance: 16,
+            placement: Placement::Cursor,
+            font_size: 20.0,
+            ttl: Duration::from_secs(60),
+            snooze: Duration::from_secs(300),
+            countdown: Duration::from_secs(300),
+            language: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        if let Ok(dir) = env::var("XDG_CONFIG_HOME") {
+            return Some(PathBuf::from(dir).join("fonotes").join("config"));
+        }
+        if let Ok(dir) = env::var("APPDATA") {
+            return Some(PathBuf::from(dir).join("fonotes").join("config"));
+        }
+        env::var("HOME").ok().map(|dir| {
+            PathBuf::from(dir)
+                .join(".config")
+                .join("fonotes")
+                .join("config")
+        })
+    }
+
+    pub fn load() -> Self {
+        match Config::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(text) => Config::parse(&text),
+            None => Config::default(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut config = Config::default();
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                eprintln!("config: can't parse line `{line}`");
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "theme" => match Theme::by_name(value) {
+                    Some(theme) => config.theme = theme,
+                    None => eprintln!("config: unknown theme `{value}`"),
+                },
+                "title_bar" => match parse_bool(value) {
+                    Some(title_bar) => config.title_bar = title_bar,
+                    None => eprintln!("config: `{key}` must be true or false"),
+                },
+                "export_dir" => config.export_dir = expand_home(value),
+                "snap_distance" => match value.parse() {
+                    Ok(distance) => config.snap_distance = distance,
+                    Err(_) => eprintln!("config: `{key}` must be a number of pixels"),
+                },
+                "placement" => match Placement::by_name(value) {
+                    Some(placement) => config.placement = placement,
+                    None => eprintln!("config: unknown placement `{value}`"),
+                },
+                "font_size" => match value.parse() {
+                    Ok(size) if size > 0.0 => config.font_size = size,
+                    _ => eprintln!("config: `{key}` must be a positive number"),
+                },
+                "ttl" => match value.parse() {
+                    Ok(secs) if secs > 0 && secs <= MAX_TTL.as_secs() => {
+                        config.ttl = Duration::from_secs(secs)
+                    }
+                    _ => eprintln!("config: `{key}` must be a number of seconds, at most a year"),
+                },
+                "snooze" => match value.parse() {
+                    Ok(secs) if secs > 0 => config.snooze = Duration::from_secs(secs),
+                    _ => eprintln!("config: `{key}` must be a positive number of seconds"),
+                },
+                "language" if value == "auto" => config.language = None,
+                "language" => match Language::by_name(value) {
+                    Some(language) => config.language = Some(language),
+                    None => eprintln!("config: unknown language `{value}`"),
+                },
+                "countdown" => match value.parse() {
+                    Ok(secs) if secs > 0 => config.countdown = Duration::from_secs(secs),
+                    _ => eprintln!("config: `{key}` must be a positive number of seconds"),
+                },
+                _ => eprintln!("config: unknown key `{key}`"),
+            }
+        }
+
+        config
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Where notes waiting for later are kept
+pub fn data_dir() -> Option<PathBuf> {
+    if let Ok(dir) = env::var("XDG_DATA_HOME") {
+        return Some(PathBuf::from(dir).join("fonotes"));
+    }
+    if let Ok(dir) = env::var("APPDATA") {
+        return Some(PathBuf::from(dir).join("fonotes"));
+    }
+    home_dir().map(|dir| dir.join(".local").join("share").join("fonotes"))
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from)
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,335 @@
+use tiny_skia::Color;
+
+use crate::render::expand_tabs;
+use crate::rich::{Line, Span, Style};
+use crate::theme::{mix, Theme};
+
+/// Side by side columns longer than this are cut, so one line doesn't shrink the rest
+const MAX_COLUMN: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// `diff --git`, `index`, `---` and `+++` lines before the hunks of a file
+    File,
+    Hunk,
+    Context,
+    Added,
+    Removed,
+    /// `\ No newline at end of file`
+    Note,
+}
+
+impl Kind {
+    fn style(self, theme: &Theme) -> Style {
+        // fixed hues pulled a bit towards the text color, like the code colors
+        let hue = |r, g, b| Some(mix(Color::from_rgba8(r, g, b, 255), theme.text, 0.2));
+        let mono = Style {
+            mono: true,
+            ..Style::default()
+        };
+        match self {
+            Kind::File => Style { bold: true, ..mono },
+            Kind::Hunk => Style {
+                color: hue(50, 100, 220),
+                ..mono
+            },
+            Kind::Context => mono,
+            Kind::Added => Style {
+                color: hue(30, 140, 50),
+                background: Some(tint(30, 180, 60)),
+                ..mono
+            },
+            Kind::Removed => Style {
+                color: hue(200, 40, 40),
+                background: Some(tint(220, 50, 50)),
+                ..mono
+            },
+            Kind::Note => Style {
+                color: Some(faded(theme.text)),
+                italic: true,
+                ..mono
+            },
+        }
+    }
+}
+
+fn tint(r: u8, g: u8, b: u8) -> Color {
+    Color::from_rgba8(r, g, b, 40)
+}
+
+fn faded(color: Color) -> Color {
+    let mut color = color;
+    color.apply_opacity(0.5);
+    color
+}
+
+/// Start and length of the old and the new side of a `@@ -1,3 +1,4 @@` line
+fn parse_hunk(line: &str) -> Option<(usize, usize, usize, usize)> {
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let old = range(parts.next()?.strip_prefix('-')?)?;
+    let new = range(parts.next()?.strip_prefix('+')?)?;
+    (parts.next()? == "@@").then_some((old.0, old.1, new.0, new.1))
+}
+
+/// Whether the text is a unified diff, like `diff -u` and `git diff` make
+pub fn is_diff(text: &str) -> bool {
+    text.lines().any(|line| parse_hunk(line).is_some())
+}
+
+/// Kind of every line, with the old and new line numbers of the ones in hunks
+fn classify(text: &str) -> Vec<(Kind, &str, Option<usize>, Option<usize>)> {
+    let (mut old_left, mut new_left) = (0, 0);
+    let (mut old_number, mut new_number) = (0, 0);
+
+    text.lines()
+        .map(|line| {
+            if let Some((old_start, old_len, new_start, new_len)) = parse_hunk(line) {
+                (old_left, new_left) = (old_len, new_len);
+                (old_number, new_number) = (old_start, new_start);
+                return (Kind::Hunk, line, None, None);
+            }
+            // the note comes after the last line of a hunk
+            if line.starts_with('\\') {
+                return (Kind::Note, line, None, None);
+            }
+            // outside of hunks `---` and `+++` are file names, not changed lines
+            if old_left == 0 && new_left == 0 {
+                return (Kind::File, line, None, None);
+            }
+
+            match line.chars().next() {
+                Some('+') => {
+                    new_left = new_left.saturating_sub(1);
+                    new_number += 1;
+                    (Kind::Added, &line[1..], None, Some(new_number - 1))
+                }
+                Some('-') => {
+                    old_left = old_left.saturating_sub(1);
+                    old_number += 1;
+                    (Kind::Removed, &line[1..], Some(old_number - 1), None)
+                }
+                _ => {
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                    old_number += 1;
+                    new_number += 1;
+                    let text = line.get(1..).unwrap_or_default();
+                    (
+                        Kind::Context,
+                        text,
+                        Some(old_number - 1),
+                        Some(new_number - 1),
+                    )
+                }
+            }
+        })
+        .collect()
+}
+
+/// The diff as it is, with added and removed lines colored
+pub fn unified(text: &str, theme: &Theme) -> Vec<Line> {
+    classify(text)
+        .into_iter()
+        .map(|(kind, line, ..)| {
+            let style = kind.style(theme);
+            let marker = match kind {
+                Kind::Added => "+",
+                Kind::Removed => "-",
+                Kind::Context => " ",
+                _ => "",
+            };
+            Line {
+                spans: vec![Span::new(
+                    expand_tabs(&format!("{marker}{line}"), 4),
+                    Style {
+                        background: None,
+                        ..style
+                    },
+                )],
+                background: style.background,
+                wrap: false,
+                ..Line::default()
+            }
+        })
+        .collect()
+}
+
+/// One side of a side by side row: line number, text and kind
+type Half<'a> = Option<(usize, &'a str, Kind)>;
+
+enum Row<'a> {
+    Pair(Half<'a>, Half<'a>),
+    /// File and hunk headers go across both sides
+    Across(Kind, &'a str),
+}
+
+/// The old lines on the left and the new ones on the right, changes next to each other
+pub fn side_by_side(text: &str, theme: &Theme) -> Vec<Line> {
+    let lines = classify(text);
+    let mut rows: Vec<Row> = Vec::new();
+    let mut removed: Vec<(usize, &str)> = Vec::new();
+    let mut added: Vec<(usize, &str)> = Vec::new();
+
+    // removed lines are paired with the added ones that follow them
+    let flush = |rows: &mut Vec<_>, removed: &mut Vec<_>, added: &mut Vec<_>| {
+        for i in 0..removed.len().max(added.len()) {
+            let old = removed.get(i).map(|(n, line)| (*n, *line, Kind::Removed));
+            let new = added.get(i).map(|(n, line)| (*n, *line, Kind::Added));
+            rows.push(Row::Pair(old, new));
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for (kind, line, old, new) in lines {
+        match (kind, old, new) {
+            (Kind::Removed, Some(old), _) => {
+                if !added.is_empty() {
+                    flush(&mut rows, &mut removed, &mut added);
+                }
+                removed.push((old, line));
+            }
+            (Kind::Added, _, Some(new)) => added.push((new, line)),
+            (Kind::Context, Some(old), Some(new)) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(Row::Pair(
+                    Some((old, line, Kind::Context)),
+                    Some((new, line, Kind::Context)),
+                ));
+            }
+            _ => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(Row::Across(kind, line));
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+
+    let cut = |line: &str| {
+        let line = expand_tabs(line, 4);
+        match line.char_indices().nth(MAX_COLUMN) {
+            Some((end, _)) => format!("{}…", &line[..end]),
+            None => line,
+        }
+    };
+    let digits = rows
+        .iter()
+        .flat_map(|row| match row {
+            Row::Pair(old, new) => [*old, *new],
+            Row::Across(..) => [None, None],
+        })
+        .filter_map(|half| half.map(|(number, ..)| number.to_string().len()))
+        .max()
+        .unwrap_or(1);
+    let width = rows
+        .iter()
+        .filter_map(|row| match row {
+            Row::Pair(Some((_, line, _)), _) => Some(cut(line).chars().count()),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0);
+
+    let number_style = Style {
+        color: Some(faded(theme.text)),
+        mono: true,
+        ..Style::default()
+    };
+    let half = |half: Half, pad: bool| {
+        let (number, text, style) = match half {
+            Some((number, line, kind)) => (number.to_string(), cut(line), kind.style(theme)),
+            None => (String::new(), String::new(), Kind::Context.style(theme)),
+        };
+        let text = if pad { format!("{text:width$}") } else { text };
+        [
+            Span::new(format!("{number:>digits$} "), number_style),
+            Span::new(text, style),
+        ]
+    };
+
+    rows.into_iter()
+        .map(|row| match row {
+            Row::Pair(old, new) => {
+                let mut spans = Vec::from(half(old, true));
+                spans.push(Span::new(" │ ", number_style));
+                spans.extend(half(new, false));
+                Line {
+                    spans,
+                    wrap: false,
+                    ..Line::default()
+                }
+            }
+            Row::Across(kind, line) => {
+                let style = kind.style(theme);
+                Line {
+                    spans: vec![Span::new(expand_tabs(line, 4), style)],
+                    wrap: false,
+                    ..Line::default()
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/f b/f\n\
+                        --- a/f\n\
+                        +++ b/f\n\
+                        @@ -1,3 +1,3 @@\n\
+                        \x20one\n\
+                        -two\n\
+                        +2\n\
+                        \x20three\n\
+                        \\ No newline at end of file";
+
+    #[test]
+    fn detects_diffs() {
+        assert!(is_diff(DIFF));
+        assert!(!is_diff("--- not\n+++ a diff\n- item"));
+        assert_eq!(parse_hunk("@@ -5 +7,2 @@ fn main"), Some((5, 1, 7, 2)));
+        assert_eq!(parse_hunk("@@ -a +1 @@"), None);
+    }
+
+    #[test]
+    fn classifies_lines() {
+        let lines = classify(DIFF);
+        let kinds: Vec<Kind> = lines.iter().map(|(kind, ..)| *kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                Kind::File,
+                Kind::File,
+                Kind::File,
+                Kind::Hunk,
+                Kind::Context,
+                Kind::Removed,
+                Kind::Added,
+                Kind::Context,
+                Kind::Note,
+            ]
+        );
+
+        assert_eq!(lines[4], (Kind::Context, "one", Some(1), Some(1)));
+        assert_eq!(lines[5], (Kind::Removed, "two", Some(2), None));
+        assert_eq!(lines[6], (Kind::Added, "2", None, Some(2)));
+        assert_eq!(lines[7], (Kind::Context, "three", Some(3), Some(3)));
+    }
+
+    #[test]
+    fn file_names_after_a_hunk_are_not_changes() {
+        let text = "@@ -1 +1 @@\n-a\n+b\n--- a/g\n+++ b/g\n@@ -1 +1 @@\n-c\n+d";
+        let kinds: Vec<Kind> = classify(text).iter().map(|(kind, ..)| *kind).collect();
+        assert_eq!(kinds[3..5], [Kind::File, Kind::File]);
+        assert_eq!(kinds[6..], [Kind::Removed, Kind::Added]);
+    }
+}
@@ -0,0 +1,161 @@
+use fontdue::Font;
+use tiny_skia::{
+    BlendMode, Color, FilterQuality, IntRect, Pixmap, PixmapPaint, Point, Rect, Transform,
+};
+
+use crate::measure::snap;
+use crate::render::{draw_glyphs, fill_rect, image_transform, text_width};
+use crate::theme::Theme;
+
+const FONT_SIZE: f32 = 12.0;
+const LABEL_HEIGHT: f32 = 20.0;
+
+/// A change to the pixels of an image note
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edit {
+    Crop(IntRect),
+    /// A quarter turn clockwise
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl Edit {
+    /// Where the pixels of an image of the given size go, and the size of the result
+    pub fn transform(self, width: u32, height: u32) -> (Transform, (u32, u32)) {
+        let (w, h) = (width as f32, height as f32);
+        match self {
+            Edit::Crop(rect) => (
+                Transform::from_translate(-rect.x() as f32, -rect.y() as f32),
+                (rect.width(), rect.height()),
+            ),
+            Edit::Rotate => (
+                Transform::from_row(0.0, 1.0, -1.0, 0.0, h, 0.0),
+                (height, width),
+            ),
+            Edit::FlipHorizontal => (
+                Transform::from_row(-1.0, 0.0, 0.0, 1.0, w, 0.0),
+                (width, height),
+            ),
+            Edit::FlipVertical => (
+                Transform::from_row(1.0, 0.0, 0.0, -1.0, 0.0, h),
+                (width, height),
+            ),
+        }
+    }
+
+    pub fn apply(self, image: &Pixmap) -> Pixmap {
+        let (transform, (width, height)) = self.transform(image.width(), image.height());
+        let mut edited = Pixmap::new(width, height).unwrap();
+        // whole pixels are moved, nothing is blended or smoothed
+        let paint = PixmapPaint {
+            blend_mode: BlendMode::Source,
+            quality: FilterQuality::Nearest,
+            ..PixmapPaint::default()
+        };
+        edited.draw_pixmap(0, 0, image.as_ref(), &paint, transform, None);
+        edited
+    }
+}
+
+/// Selecting the part of an image note to crop it to
+#[derive(Debug, Clone, Default)]
+pub struct Crop {
+    /// Where the drag started and where it is now, on pixel edges
+    span: Option<(Point, Point)>,
+    pub dragging: bool,
+}
+
+impl Crop {
+    pub fn start(&mut self, point: Point, width: u32, height: u32) {
+        let point = snap(point, width, height);
+        self.span = Some((point, point));
+        self.dragging = true;
+    }
+
+    pub fn drag_to(&mut self, point: Point, width: u32, height: u32) {
+        if let Some((_, end)) = &mut self.span {
+            *end = snap(point, width, height);
+        }
+    }
+
+    /// The selection in image pixels, `None` while nothing is selected
+    pub fn selection(&self) -> Option<IntRect> {
+        let (start, end) = self.span?;
+        IntRect::from_ltrb(
+            start.x.min(end.x) as i32,
+            start.y.min(end.y) as i32,
+            start.x.max(end.x) as i32,
+            start.y.max(end.y) as i32,
+        )
+    }
+
+    /// Dims the image around the selection, with its size under it
+    pub fn draw(
+        &self,
+        pixmap: &mut Pixmap,
+        area: Rect,
+        (width, height): (u32, u32),
+        font: &Font,
+        theme: &Theme,
+    ) {
+        let Some(selection) = self.selection() else {
+            return;
+        };
+        let transform = image_transform(area, width, height);
+        let mut corners = [
+            Point::from_xy(selection.left() as f32, selection.top() as f32),
+            Point::from_xy(selection.right() as f32, selection.bottom() as f32),
+        ];
+        transform.map_points(&mut corners);
+        let [start, end] = corners;
+
+        let shade = Color::from_rgba8(0, 0, 0, 120);
+        for (left, top, right, bottom) in [
+            (area.left(), area.top(), area.right(), start.y),
+            (area.left(), end.y, area.right(), area.bottom()),
+            (area.left(), start.y, start.x, end.y),
+            (end.x, start.y, area.right(), end.y),
+        ] {
+            if let Some(rect) = Rect::from_ltrb(left, top, right, bottom) {
+                fill_rect(pixmap, rect, shade);
+            }
+        }
+        for (x, y, w, h) in [
+            (start.x, start.y, end.x - start.x, 1.0),
+            (start.x, end.y - 1.0, end.x - start.x, 1.0),
+            (start.x, start.y, 1.0, end.y - start.y),
+            (end.x - 1.0, start.y, 1.0, end.y - start.y),
+        ] {
+            if let Some(line) = Rect::from_xywh(x, y, w, h) {
+                fill_rect(pixmap, line, Color::WHITE);
+            }
+        }
+
+        let label = format!(
+            "{} × {} px · Enter crops",
+            selection.width(),
+            selection.height()
+        );
+        let label_width = text_width(&label, FONT_SIZE, font) + 8.0;
+        // under the selection, kept inside the note
+        let x = start.x.min(area.right() - label_width).max(area.left());
+        let y = (end.y + 4.0)
+            .min(area.bottom() - LABEL_HEIGHT)
+            .max(area.top());
+        if let Some(rect) = Rect::from_xywh(x, y, label_width, LABEL_HEIGHT) {
+            fill_rect(pixmap, rect, theme.background);
+        }
+        draw_glyphs(
+            pixmap,
+            &label,
+            x + 4.0,
+            y + 14.0,
+            FONT_SIZE,
+            font,
+            theme.text,
+            false,
+            false,
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+/// Notes fade out during the last seconds before they close
+const FADE_TIME: Duration = Duration::from_secs(3);
+const TICK: Duration = Duration::from_millis(250);
+const FADE_TICK: Duration = Duration::from_millis(30);
+/// Longest time a note can be set to close after, a year
+pub const MAX_TTL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Countdown after which a note closes by itself
+#[derive(Debug, Clone, Copy)]
+pub struct Expiry {
+    start: Instant,
+    ttl: Duration,
+}
+
+impl Expiry {
+    pub fn new(ttl: Duration) -> Self {
+        Expiry {
+            start: Instant::now(),
+            ttl,
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        match self.start.checked_add(self.ttl) {
+            Some(end) => end.saturating_duration_since(now),
+            None => Duration::MAX,
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.remaining(now).is_zero()
+    }
+
+    /// Part of the time that is left, from 1 down to 0
+    pub fn fraction_left(&self, now: Instant) -> f32 {
+        self.remaining(now).as_secs_f32() / self.ttl.as_secs_f32()
+    }
+
+    /// Opacity multiplier, goes down to 0 while fading out
+    pub fn fade(&self, now: Instant) -> f32 {
+        (self.remaining(now).as_secs_f32() / FADE_TIME.as_secs_f32()).min(1.0)
+    }
+
+    /// When the note has to be redrawn next, often while it fades out
+    pub fn next_tick(&self, now: Instant) -> Instant {
+        let remaining = self.remaining(now);
+        if remaining <= FADE_TIME {
+            now + FADE_TICK.min(remaining)
+        } else {
+            now + TICK.min(remaining - FADE_TIME)
+        }
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..=59 => format!("{secs} s"),
+        60..=3599 if secs.is_multiple_of(60) => format!("{} min", secs / 60),
+        60..=3599 => format!("{}:{:02}", secs / 60, secs % 60),
+        _ => format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60),
+    }
+}
@@ -0,0 +1,150 @@
+use fontdue::Font;
+use tiny_skia::{Color, ColorU8, Pixmap, Rect};
+
+use crate::render::{draw_glyphs, fill_rect, image_point, text_width};
+use crate::theme::Theme;
+
+/// Pixels around the picked one the loupe shows, across and down
+const GRID: i32 = 11;
+const ZOOM: f32 = 8.0;
+const FONT_SIZE: f32 = 12.0;
+const LABEL_HEIGHT: f32 = 34.0;
+/// Gap between the cursor and the loupe
+const OFFSET: f32 = 16.0;
+
+/// Picking colors from an image note
+#[derive(Debug, Clone, Default)]
+pub struct Eyedropper {
+    /// Cursor in the window, `None` while it's outside of it
+    pub cursor: Option<(f32, f32)>,
+    /// Image pixel that was just copied
+    copied: Option<(u32, u32)>,
+}
+
+/// Image pixel under the window point, `None` outside of the image
+pub fn pixel_at(image: &Pixmap, area: Rect, x: f32, y: f32) -> Option<(u32, u32)> {
+    let point = image_point(area, image.width(), image.height(), x, y)?;
+    let inside = point.x >= 0.0
+        && point.y >= 0.0
+        && point.x < image.width() as f32
+        && point.y < image.height() as f32;
+    inside.then_some((point.x as u32, point.y as u32))
+}
+
+fn color_at(image: &Pixmap, x: i32, y: i32) -> Option<ColorU8> {
+    let (x, y) = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+    image.pixel(x, y).map(|pixel| pixel.demultiply())
+}
+
+pub fn hex(color: ColorU8) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        color.red(),
+        color.green(),
+        color.blue()
+    )
+}
+
+impl Eyedropper {
+    /// Color under the cursor as hex, remembered to show it was copied
+    pub fn pick(&mut self, image: &Pixmap, area: Rect) -> Option<String> {
+        let (x, y) = self.cursor?;
+        let (px, py) = pixel_at(image, area, x, y)?;
+        self.copied = Some((px, py));
+        color_at(image, px as i32, py as i32).map(hex)
+    }
+
+    /// Magnified pixels around the cursor with the color and the coordinates under them
+    pub fn draw(
+        &self,
+        pixmap: &mut Pixmap,
+        image: &Pixmap,
+        area: Rect,
+        font: &Font,
+        theme: &Theme,
+    ) {
+        let Some((x, y)) = self.cursor else {
+            return;
+        };
+        let Some((px, py)) = pixel_at(image, area, x, y) else {
+            return;
+        };
+
+        let size = GRID as f32 * ZOOM;
+        // next to the cursor, on the other side of it near the edges
+        let left = if x + OFFSET + size > area.right() {
+            x - OFFSET - size
+        } else {
+            x + OFFSET
+        };
+        let top = if y + OFFSET + size + LABEL_HEIGHT > area.bottom() {
+            y - OFFSET - size - LABEL_HEIGHT
+        } else {
+            y + OFFSET
+        };
+
+        if let Some(frame) = Rect::from_xywh(left - 1.0, top - 1.0, size + 2.0, size + 2.0) {
+            fill_rect(pixmap, frame, theme.text);
+        }
+        let half = GRID / 2;
+        for dy in -half..=half {
+            for dx in -half..=half {
+                let color = color_at(image, px as i32 + dx, py as i32 + dy)
+                    .map_or(theme.background, |c| {
+                        Color::from_rgba8(c.red(), c.green(), c.blue(), 255)
+                    });
+                let cell = Rect::from_xywh(
+                    left + (dx + half) as f32 * ZOOM,
+                    top + (dy + half) as f32 * ZOOM,
+                    ZOOM,
+                    ZOOM,
+                );
+                if let Some(cell) = cell {
+                    fill_rect(pixmap, cell, color);
+                }
+            }
+        }
+
+        // the picked pixel is outlined in the middle
+        let middle = (left + half as f32 * ZOOM, top + half as f32 * ZOOM);
+        for (x, y, w, h) in [
+            (middle.0 - 1.0, middle.1 - 1.0, ZOOM + 2.0, 1.0),
+            (middle.0 - 1.0, middle.1 + ZOOM, ZOOM + 2.0, 1.0),
+            (middle.0 - 1.0, middle.1, 1.0, ZOOM),
+            (middle.0 + ZOOM, middle.1, 1.0, ZOOM),
+        ] {
+            if let Some(line) = Rect::from_xywh(x, y, w, h) {
+                fill_rect(pixmap, line, theme.text);
+            }
+        }
+
+        let color = color_at(image, px as i32, py as i32).map_or_else(String::new, hex);
+        let position = if self.copied == Some((px, py)) {
+            format!("{px}, {py} · copied")
+        } else {
+            format!("{px}, {py}")
+        };
+        let lines = [color, position];
+
+        let width = lines
+            .iter()
+            .map(|line| text_width(line, FONT_SIZE, font) + 8.0)
+            .fold(size + 2.0, f32::max);
+        if let Some(label) = Rect::from_xywh(left - 1.0, top + size + 1.0, width, LABEL_HEIGHT) {
+            fill_rect(pixmap, label, theme.background);
+        }
+        for (i, line) in lines.iter().enumerate() {
+            draw_glyphs(
+                pixmap,
+                line,
+                left + 4.0,
+                top + size + 14.0 + i as f32 * 15.0,
+                FONT_SIZE,
+                font,
+                theme.text,
+                false,
+                false,
+            );
+        }
+    }
+}
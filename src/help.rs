@@ -0,0 +1,178 @@
+use fontdue::Font;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Transform};
+
+use crate::render::{fill_rect, fit_text, render_text};
+use crate::theme::Theme;
+
+const FONT_SIZE: f32 = 13.0;
+const LINE_HEIGHT: f32 = 18.0;
+const PADDING: f32 = 10.0;
+
+/// Keys and what they do, the test below checks the README lists the same keys
+pub const BINDINGS: &[(&str, &str)] = &[
+    ("Arrows", "Move note"),
+    ("Shift + Arrows", "Resize note"),
+    ("Tab, Shift + Tab", "Next, previous note"),
+    ("Space, double click", "Collapse, expand"),
+    ("Esc", "Close note"),
+    ("Menu, Shift + F10", "Open menu"),
+    ("Ctrl + C", "Copy to clipboard"),
+    ("Ctrl + E", "Export to file"),
+    ("Ctrl + D", "Duplicate"),
+    ("Ctrl + K", "Change color tag"),
+    ("Ctrl + P", "Change level"),
+    ("Ctrl + L", "Close by itself, keep"),
+    ("Ctrl + B", "Switch how text is shown"),
+    ("Page Up, Page Down", "Scroll tree, table"),
+    ("Ctrl + R", "Snooze, remind later"),
+    ("Ctrl + I", "Pick a color from image"),
+    ("Ctrl + M", "Measure image"),
+    ("Ctrl + A", "Annotate image"),
+    ("Ctrl + Z", "Undo mark"),
+    ("Ctrl + X", "Crop image"),
+    ("Ctrl + O", "Rotate image"),
+    ("Enter", "Start, pause timer"),
+    ("Backspace", "Reset timer"),
+    ("Ctrl + Scroll, Ctrl + -, Ctrl + =", "Change opacity"),
+    ("Ctrl + T", "Click-through"),
+    ("Ctrl + Alt + T", "Leave click-through"),
+    ("Ctrl + Alt + N", "Note from clipboard"),
+    ("Ctrl + Alt + Shift + N", "Temporary note"),
+    ("Ctrl + Alt + C", "Countdown"),
+    ("Ctrl + Alt + W", "Stopwatch"),
+    ("Ctrl + Alt + A", "Arrange all notes"),
+    ("Ctrl + Alt + R", "List reminders"),
+    ("F1, ?", "Toggle this help"),
+];
+
+/// Bindings that fit into an area of given height, at least one
+fn rows(height: f32) -> usize {
+    (((height - PADDING * 2.0) / LINE_HEIGHT) as usize).clamp(1, BINDINGS.len())
+}
+
+/// First binding shown after scrolling by `by` rows, the last one stays at the bottom
+pub fn scroll(first: usize, by: i32, height: f32) -> usize {
+    let last = BINDINGS.len() - rows(height);
+    (first as i32 + by).clamp(0, last as i32) as usize
+}
+
+/// Rows a page up or down scrolls by
+pub fn page(height: f32) -> i32 {
+    rows(height) as i32
+}
+
+/// Draws the list of bindings over the whole note, from the `first` one
+pub fn draw(pixmap: &mut Pixmap, font: &Font, theme: &Theme, top: f32, first: usize) {
+    let Some(area) = Rect::from_xywh(
+        0.0,
+        top,
+        pixmap.width() as f32,
+        pixmap.height() as f32 - top,
+    ) else {
+        return;
+    };
+    fill_rect(pixmap, area, theme.background);
+
+    // measured as drawn, the glyph advances come out narrower than the rendered text
+    let keys_width = BINDINGS
+        .iter()
+        .map(|(keys, _)| render_text(keys.to_string(), FONT_SIZE, font, theme.text).width() as f32)
+        .fold(0.0, f32::max);
+
+    // the note may have grown since the list was scrolled
+    let (rows, first) = (rows(area.height()), scroll(first, 0, area.height()));
+    let shown = BINDINGS.iter().enumerate().skip(first).take(rows);
+    for (row, (i, (keys, description))) in shown.enumerate() {
+        let y = top + PADDING + LINE_HEIGHT * row as f32;
+
+        // arrows on the edge rows show there are more bindings to scroll to
+        let more_above = row == 0 && first > 0;
+        let more_below = row + 1 == rows && i + 1 < BINDINGS.len();
+        if more_above || more_below {
+            let (x, y) = (area.right() - PADDING - 4.0, y + LINE_HEIGHT / 2.0);
+            let tip = if more_above { -3.0 } else { 3.0 };
+            let mut path = PathBuilder::new();
+            path.move_to(x - 4.0, y - tip);
+            path.line_to(x + 4.0, y - tip);
+            path.line_to(x, y + tip);
+            path.close();
+            let mut paint = Paint::default();
+            paint.set_color(theme.text);
+            paint.anti_alias = true;
+            if let Some(path) = path.finish() {
+                pixmap.fill_path(
+                    &path,
+                    &paint,
+                    FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+
+        // descriptions are cut before the scroll arrows
+        let x = PADDING * 2.0 + keys_width;
+        let description = fit_text(
+            description,
+            area.right() - x - PADDING * 2.0,
+            FONT_SIZE,
+            font,
+        );
+        for (x, text) in [(PADDING, keys.to_string()), (x, description)] {
+            let text = render_text(text, FONT_SIZE, font, theme.text);
+            pixmap.draw_pixmap(
+                x as i32,
+                (y + LINE_HEIGHT - text.height() as f32) as i32,
+                text.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BINDINGS;
+
+    /// Keys in the first column of the controls table of the README
+    fn readme_keys() -> Vec<(String, bool)> {
+        include_str!("../README.md")
+            .lines()
+            .skip_while(|line| *line != "## Controls")
+            .skip(3)
+            .take_while(|line| line.starts_with('|'))
+            .filter_map(|line| line.split('|').nth(1))
+            .flat_map(|keys| keys.split(", "))
+            .map(|key| {
+                let key = key.trim();
+                // mouse actions are left out of the help, keys are in backticks
+                let is_key = key.starts_with('`');
+                (key.trim_matches('`').to_string(), is_key)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bindings_match_readme() {
+        let readme = readme_keys();
+        let bindings: Vec<&str> = BINDINGS
+            .iter()
+            .flat_map(|(keys, _)| keys.split(", "))
+            .collect();
+
+        for key in &bindings {
+            assert!(
+                readme.iter().any(|(readme_key, _)| readme_key == key),
+                "{key} is missing in the README"
+            );
+        }
+        for (key, is_key) in &readme {
+            assert!(
+                !is_key || bindings.contains(&key.as_str()),
+                "{key} is missing in the help"
+            );
+        }
+    }
+}
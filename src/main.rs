@@ -1,19 +1,51 @@
+mod annotate;
+mod ansi;
+mod checklist;
+mod cli;
+mod code;
+mod config;
+mod diff;
+mod edit;
+mod expiry;
+mod eyedropper;
+mod help;
+mod markdown;
+mod measure;
+mod menu;
+mod placement;
+mod reminders;
+mod render;
+mod rich;
+mod snap;
+mod swatch;
+mod table;
+mod theme;
+mod timer;
+mod title_bar;
+mod tree;
+mod view;
+
 use std::cmp;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::mem;
 use std::num::NonZeroU32;
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 use arboard::{Clipboard, ImageData};
-use fontdue::layout::{CoordinateSystem, Layout, TextStyle};
+use chrono::{DateTime, Local};
 use fontdue::{Font, FontSettings};
 use softbuffer::{Context, Surface};
-use tiny_skia::{
-    Color, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect,
-    Transform,
-};
+use tiny_skia::{Pixmap, PixmapPaint, Point, Rect, Transform};
 
 use winit::dpi::LogicalSize;
-use winit::dpi::PhysicalPosition;
-use winit::event::{Event, MouseButton, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::event::{ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy};
+use winit::keyboard::{self, ModifiersState, NamedKey};
+use winit::monitor::MonitorHandle;
 use winit::platform::x11::EventLoopBuilderExtX11;
 use winit::window::CursorIcon;
 use winit::window::ResizeDirection;
@@ -21,18 +53,56 @@ use winit::window::{Window, WindowBuilder, WindowButtons, WindowId, WindowLevel}
 
 use rdev::*;
 
+use annotate::{Annotate, Mark};
+use checklist::Checklist;
+use cli::Args;
+use code::Language;
+use config::Config;
+use edit::{Crop, Edit};
+use expiry::Expiry;
+use eyedropper::Eyedropper;
+use measure::Measure;
+use menu::{Action, ContextMenu, MenuPage, MenuState};
+use placement::PlacementContext;
+use reminders::{RemindTime, Reminder};
+use render::{
+    fill_rect, image_data, image_point, image_transform, measure_text, rect_contains, render_image,
+    render_text_with_ln, text_width,
+};
+use rich::Fonts;
+use snap::Bounds;
+use swatch::Swatch;
+use table::Table;
+use theme::{ColorTag, Theme};
+use timer::{Timer, TimerButton, TimerKind};
+use title_bar::{TitleBarState, TitleButton, TITLE_BAR_HEIGHT};
+use tree::Tree;
+use view::TextView;
+
 use core::slice::IterMut;
 use std::cell::RefCell;
 use std::sync::Arc;
 use std::thread;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum ClipboardContent {
     Image(ImageData<'static>),
     Text(String),
+    Timer(Timer),
     None,
 }
 
+impl ClipboardContent {
+    fn kind(&self) -> &'static str {
+        match self {
+            ClipboardContent::Image(_) => "Image",
+            ClipboardContent::Text(_) => "Text",
+            ClipboardContent::Timer(_) => "Timer",
+            _ => "???",
+        }
+    }
+}
+
 fn get_clipboard(clipboard: &mut Clipboard) -> ClipboardContent {
     match clipboard.get_image() {
         Ok(i) => ClipboardContent::Image(i.to_owned_img()),
@@ -43,121 +113,6 @@ fn get_clipboard(clipboard: &mut Clipboard) -> ClipboardContent {
     }
 }
 
-fn render_char(ch: char, size: f32, font: &Font, color: Color) -> Pixmap {
-    let (metrics, bitmap) = font.rasterize(ch, size);
-
-    if ch == ' ' {
-        return Pixmap::new(size as u32, size as u32).unwrap();
-    } else if ch == '\t' {
-        return Pixmap::new(size as u32 * 2, size as u32).unwrap();
-    }
-
-    let mut pixmap = Pixmap::new(metrics.width as u32, metrics.height as u32).unwrap();
-    let data: &mut [u8] = pixmap.data_mut();
-
-    for j in 0..(metrics.width * metrics.height) {
-        data[j * 4] = color.red() as u8;
-        data[j * 4 + 1] = color.green() as u8;
-        data[j * 4 + 2] = color.blue() as u8;
-        data[j * 4 + 3] = bitmap[j];
-    }
-
-    pixmap
-}
-
-fn render_text(text: String, font_size: f32, font: &Font, color: Color) -> Pixmap {
-    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
-    layout.append(&[font], &TextStyle::new(&text, font_size, 0));
-
-    let mut text_width = 0;
-    let mut max_height = 0;
-
-    let mut chars: Vec<(Pixmap, f32, f32)> = Vec::new();
-
-    for gl in layout.glyphs() {
-        let ch = gl.parent;
-
-        let height = gl.y as i32 + gl.height as i32;
-
-        let rendered = render_char(ch, font_size, font, color);
-        text_width = gl.x as i32 + gl.width as i32;
-        if height > max_height {
-            max_height = height;
-        }
-        chars.push((rendered, gl.x, gl.y));
-    }
-
-    let mut pixmap = match Pixmap::new(text_width as u32, max_height as u32) {
-        Some(i) => i,
-        None => {
-            return Pixmap::new(1, 1).unwrap();
-        }
-    };
-    let paint = PixmapPaint::default();
-
-    for jujuk in chars {
-        let ele = jujuk.0;
-        let pos = (jujuk.1, jujuk.2);
-
-        pixmap.draw_pixmap(
-            pos.0 as i32,
-            pos.1 as i32,
-            ele.as_ref(),
-            &paint,
-            Transform::identity(),
-            None,
-        );
-    }
-
-    pixmap
-}
-
-fn render_text_with_ln(
-    text: String,
-    size: f32,
-    font: &Font,
-    line_height: i32,
-    color: Color,
-) -> Pixmap {
-    let mut width = 0;
-    let mut lines: Vec<Pixmap> = Vec::new();
-    for ele in text.split("\n") {
-        let rendered = render_text(ele.to_string(), size, font, color);
-        if rendered.width() > width {
-            width = rendered.width();
-        }
-        lines.push(rendered);
-    }
-    let height = (line_height * lines.len() as i32) as i32;
-
-    let mut pixmap = Pixmap::new(width, height as u32).unwrap();
-    let paint = PixmapPaint::default();
-
-    let mut y: i32 = 0;
-    for ele in lines {
-        pixmap.draw_pixmap(
-            0,
-            (y + (line_height - ele.height() as i32)) as i32,
-            ele.as_ref(),
-            &paint,
-            Transform::identity(),
-            None,
-        );
-        y += line_height;
-    }
-
-    pixmap
-}
-
-fn render_image(image: ImageData<'static>) -> Pixmap {
-    let mut pixmap = Pixmap::new(image.width as u32, image.height as u32).unwrap();
-    let data: &mut [u8] = pixmap.data_mut();
-    for i in 0..image.bytes.len() {
-        data[i] = image.bytes[i];
-    }
-    pixmap
-}
-
 pub trait RemoveElem<T: PartialEq> {
     fn remove_elem<F>(&mut self, predicate: F) -> Option<T>
     where
@@ -181,12 +136,100 @@ impl<T: PartialEq> RemoveElem<T> for Vec<T> {
     }
 }
 
+const OPACITY_STEP: f32 = 0.05;
+const MIN_OPACITY: f32 = 0.1;
+const RESIZE_BORDER: f64 = 20.0;
+const MIN_NOTE_SIZE: LogicalSize<f64> = LogicalSize::new(50.0, 50.0);
+/// Largest part of the monitor a new note takes on each axis
+const MAX_SCREEN_FRACTION: f32 = 0.6;
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+/// How far arrow keys move or resize a note
+const KEYBOARD_STEP: i32 = 10;
+const EXPIRY_BAR_HEIGHT: f32 = 4.0;
+const FLASH_TIME: Duration = Duration::from_secs(3);
+const FLASH_TICK: Duration = Duration::from_millis(250);
+const FLASH_BORDER: f32 = 6.0;
+
+/// What is under the mouse cursor in a note
+enum Hit {
+    /// Menu item, or `None` when the menu is open but the cursor is outside of it
+    Menu(Option<usize>),
+    Button(TitleButton),
+    Timer(TimerButton),
+    ChecklistItem(usize),
+    TreeRow(usize),
+    /// Row and column of a table cell
+    TableCell(usize, usize),
+    /// Right edge of a table column
+    TableEdge(usize),
+    /// Format row of a color swatch
+    SwatchRow(usize),
+    /// Pixel of an image note picked with the eyedropper
+    Pixel,
+    /// Image note being measured
+    Measure,
+    /// Image note being drawn over
+    Annotate,
+    /// Image note being cropped
+    Crop,
+    Resize(ResizeDirection),
+    Drag,
+}
+
 struct Note {
     window: Arc<Window>,
     window_id: WindowId,
     surface: Surface<Arc<Window>, Arc<Window>>,
     mouse_pos: PhysicalPosition<f64>,
+    modifiers: ModifiersState,
     clipboard: ClipboardContent,
+    opacity: f32,
+    click_through: bool,
+    color_tag: ColorTag,
+    created: DateTime<Local>,
+    hovered: Option<TitleButton>,
+    level: WindowLevel,
+    collapsed: bool,
+    expanded_size: PhysicalSize<u32>,
+    menu: Option<ContextMenu>,
+    last_click: Option<Instant>,
+    show_help: bool,
+    /// First binding shown in the help, it scrolls when the note is too small for all of them
+    help_scroll: usize,
+    /// Set when the note is moved by keys, so snapping doesn't pull it back
+    skip_snap: bool,
+    expiry: Option<Expiry>,
+    /// When the event loop has to redraw the note by itself, to animate it
+    redraw_at: Option<Instant>,
+    /// The note is hidden until the reminder is due
+    reminder: Option<Reminder>,
+    flash_until: Option<Instant>,
+    view: TextView,
+    /// Language of a text note shown as code
+    language: Option<Language>,
+    /// Checked state of a text note shown as a checklist, kept while it's shown otherwise
+    checklist: Option<Checklist>,
+    /// Parsed data of a text note shown as a tree, with its folds
+    tree: Option<Tree>,
+    table: Option<Table>,
+    swatch: Option<Swatch>,
+    /// Pixels of an image note, so redraws and picking don't copy them out of the image data
+    image: Option<Pixmap>,
+    /// Set while colors are picked from an image note
+    eyedropper: Option<Eyedropper>,
+    /// Set while an image note is measured
+    measure: Option<Measure>,
+    /// Marks drawn over an image note, oldest first
+    annotations: Vec<Mark>,
+    /// Set while an image note is drawn over
+    annotate: Option<Annotate>,
+    /// Set while the part of an image note to keep is selected
+    crop: Option<Crop>,
+    /// Image note as it was before it was cropped, rotated or flipped,
+    /// with where the edits moved its pixels
+    original: Option<(ImageData<'static>, Transform)>,
+    /// Column of the table being resized and its left edge
+    column_drag: Option<(usize, f32)>,
 }
 
 impl PartialEq for Note {
@@ -205,412 +248,2178 @@ impl Note {
         let mouse_pos: PhysicalPosition<f64> = PhysicalPosition::new(0.0, 0.0);
 
         let window_id = arc_window.clone().id();
+        let expanded_size = arc_window.inner_size();
+
+        let (view, language) = match &clipboard {
+            ClipboardContent::Text(t) => (TextView::detect(t), Language::detect(t)),
+            _ => (TextView::Text, None),
+        };
+        let checklist = match &clipboard {
+            ClipboardContent::Text(t) if view == TextView::Checklist => Some(Checklist::parse(t)),
+            _ => None,
+        };
+        let tree = match &clipboard {
+            ClipboardContent::Text(t) if view == TextView::Tree => Tree::parse(t),
+            _ => None,
+        };
+        let table = match &clipboard {
+            ClipboardContent::Text(t) if view == TextView::Table => Table::parse(t),
+            _ => None,
+        };
+        let swatch = match &clipboard {
+            ClipboardContent::Text(t) if view == TextView::Swatch => Swatch::parse(t),
+            _ => None,
+        };
+        let image = match &clipboard {
+            ClipboardContent::Image(i) => Some(render_image(i.clone())),
+            _ => None,
+        };
 
         Note {
             window: arc_window.clone(),
             window_id,
             surface,
             mouse_pos,
+            modifiers: ModifiersState::empty(),
             clipboard,
+            opacity: 1.0,
+            click_through: false,
+            color_tag: ColorTag::None,
+            created: Local::now(),
+            hovered: None,
+            level: WindowLevel::AlwaysOnTop,
+            collapsed: false,
+            expanded_size,
+            menu: None,
+            last_click: None,
+            show_help: false,
+            help_scroll: 0,
+            skip_snap: false,
+            expiry: None,
+            redraw_at: None,
+            reminder: None,
+            flash_until: None,
+            view,
+            tree,
+            table,
+            swatch,
+            image,
+            eyedropper: None,
+            measure: None,
+            annotations: Vec::new(),
+            annotate: None,
+            crop: None,
+            original: None,
+            column_drag: None,
+            language,
+            checklist,
         }
     }
-}
 
-fn create_event_loop() -> EventLoop<MyUserEvent> {
-    let event_loop: EventLoop<MyUserEvent> = EventLoopBuilder::with_user_event()
-        .with_any_thread(true)
-        .build()
-        .unwrap();
+    /// The content as it's copied, exported and duplicated
+    fn content(&self, font: &Font) -> ClipboardContent {
+        match (&self.clipboard, &self.checklist) {
+            (ClipboardContent::Text(_), Some(checklist)) if self.view == TextView::Checklist => {
+                ClipboardContent::Text(checklist.to_text())
+            }
+            (ClipboardContent::Image(_), _) if !self.annotations.is_empty() => match &self.image {
+                Some(image) => ClipboardContent::Image(image_data(annotate::bake(
+                    image,
+                    &self.annotations,
+                    font,
+                ))),
+                None => self.clipboard.clone(),
+            },
+            _ => self.clipboard.clone(),
+        }
+    }
 
-    event_loop.set_control_flow(ControlFlow::Poll);
-    event_loop.set_control_flow(ControlFlow::Wait);
+    fn shown_checklist(&self) -> Option<&Checklist> {
+        self.checklist
+            .as_ref()
+            .filter(|_| self.view == TextView::Checklist)
+    }
 
-    event_loop
-}
+    fn shown_tree(&self) -> Option<&Tree> {
+        self.tree.as_ref().filter(|_| self.view == TextView::Tree)
+    }
+
+    fn shown_table(&self) -> Option<&Table> {
+        self.table.as_ref().filter(|_| self.view == TextView::Table)
+    }
+
+    fn shown_swatch(&self) -> Option<&Swatch> {
+        self.swatch
+            .as_ref()
+            .filter(|_| self.view == TextView::Swatch)
+    }
+
+    fn draw_content(
+        &self,
+        pixmap: &mut Pixmap,
+        area: Rect,
+        fonts: &Fonts,
+        theme: &Theme,
+        font_size: f32,
+    ) {
+        if let Some(checklist) = self.shown_checklist() {
+            return checklist.draw(pixmap, area, fonts.regular, theme);
+        }
+        if let Some(tree) = self.shown_tree() {
+            return tree.draw(pixmap, area, fonts, theme);
+        }
+        if let Some(table) = self.shown_table() {
+            return table.draw(pixmap, area, fonts.regular, theme);
+        }
+        if let Some(swatch) = self.shown_swatch() {
+            return swatch.draw(pixmap, area, fonts.regular, theme);
+        }
+
+        match (&self.clipboard, self.view) {
+            (ClipboardContent::Text(t), TextView::Markdown) => {
+                // markdown wraps to the note instead of scaling with it
+                let lines = markdown::parse(t, theme);
+                let (size, lines) = rich::fit(&lines, area, fonts, font_size);
+                rich::draw(pixmap, area, &lines, size, fonts, theme.text);
+            }
+            (ClipboardContent::Text(t), TextView::Terminal) => {
+                let lines = ansi::parse(t);
+                let (size, lines) = rich::fit(&lines, area, fonts, font_size);
+                rich::draw(pixmap, area, &lines, size, fonts, theme.text);
+            }
+            (ClipboardContent::Text(t), TextView::Diff | TextView::SideBySide) => {
+                let lines = if self.view == TextView::Diff {
+                    diff::unified(t, theme)
+                } else {
+                    diff::side_by_side(t, theme)
+                };
+                let (size, lines) = rich::fit(&lines, area, fonts, font_size);
+                rich::draw(pixmap, area, &lines, size, fonts, theme.text);
+            }
+            (ClipboardContent::Text(t), TextView::Code) => {
+                let language = self.language.unwrap_or(Language::ALL[0]);
+                let lines = code::highlight(t, language, theme);
+                let (size, lines) = rich::fit(&lines, area, fonts, font_size);
+                rich::draw(pixmap, area, &lines, size, fonts, theme.text);
+            }
+            (ClipboardContent::Image(_), _) => {
+                if let Some(image) = &self.image {
+                    draw_image(pixmap, area, image);
+                }
+            }
+            _ => draw_clipboard(pixmap, area, &self.clipboard, fonts.regular, theme),
+        }
 
-fn get_window<'a>(windows: IterMut<'a, Note>, id: WindowId) -> Option<&'a mut Note> {
-    for note in windows {
-        if note.window_id == id {
-            return Some(note);
+        if let Some(size) = self.image_size() {
+            annotate::draw_marks(pixmap, area, size, &self.annotations, fonts.regular);
+        }
+        if let (Some(image), Some(eyedropper)) = (&self.image, &self.eyedropper) {
+            eyedropper.draw(pixmap, image, area, fonts.regular, theme);
+        }
+        if let (Some(size), Some(measure)) = (self.image_size(), &self.measure) {
+            measure.draw(pixmap, area, size, fonts.regular, theme);
+        }
+        if let (Some(size), Some(annotate)) = (self.image_size(), &self.annotate) {
+            annotate.draw(pixmap, area, size, fonts.regular, theme);
+        }
+        if let (Some(size), Some(crop)) = (self.image_size(), &self.crop) {
+            crop.draw(pixmap, area, size, fonts.regular, theme);
         }
     }
 
-    None
-}
+    fn image_size(&self) -> Option<(u32, u32)> {
+        match &self.clipboard {
+            ClipboardContent::Image(i) => Some((i.width as u32, i.height as u32)),
+            _ => None,
+        }
+    }
 
-fn draw_debug_rect<'a>(pixmap: &'a mut Pixmap, x: i32, y: i32, w: i32, h: i32) {
-    let path = PathBuilder::from_rect(
-        Rect::from_xywh(
-            x as f32, 
-            y as f32, 
-            w as f32,
-            h as f32
-        ).unwrap());
-
-    let mut paint = Paint::default();
-    paint.set_color_rgba8(220, 0, 0, 255);
-
-    pixmap.fill_path(
-        &path,
-        &paint,
-        FillRule::EvenOdd,
-        Transform::identity(),
-        None,
-    );
-}
+    /// The mouse position on the image of an image note, in image pixels
+    fn image_point(&self, title_bar: bool) -> Option<Point> {
+        let (width, height) = self.image_size()?;
+        let area = self.content_area(title_bar)?;
+        let (x, y) = (self.mouse_pos.x as f32, self.mouse_pos.y as f32);
+        image_point(area, width, height, x, y)
+    }
 
-fn run_event_loop(event_loop: EventLoop<MyUserEvent>, windows: RefCell<Vec<Note>>) {
-    let font: Font = Font::from_bytes(
-        include_bytes!("../resources/Roboto.ttf") as &[u8],
-        FontSettings::default(),
-    )
-    .unwrap();
+    /// Leaves the annotate mode, keeping the label being typed
+    fn stop_annotating(&mut self) {
+        if let Some(mark) = self.annotate.take().and_then(|mut a| a.finish()) {
+            self.annotations.push(mark);
+        }
+    }
 
-    event_loop
-        .run(move |event, elwt| {
-            let mut windows_local = windows.borrow_mut();
+    /// Crops, rotates or flips an image note, the marks on it move along
+    fn edit_image(&mut self, edit: Edit, font: &Font, config: &Config) {
+        let (ClipboardContent::Image(i), Some(image)) = (&self.clipboard, &self.image) else {
+            return;
+        };
 
-            match event {
-                Event::Resumed => {}
-                Event::UserEvent(win) => {
-                    let built = win.window_builder.build(&elwt).unwrap();
-                    let win = Note::new(built, win.clipboard);
-                    windows_local.push(win);
-                }
-                Event::WindowEvent { window_id, event } => {
-                    let win = match get_window(windows_local.iter_mut(), window_id) {
-                        Some(i) => i,
-                        None => {
-                            return;
-                        }
-                    };
+        let (transform, _) = edit.transform(image.width(), image.height());
+        let (_, moved) = self
+            .original
+            .get_or_insert_with(|| (i.clone(), Transform::identity()));
+        *moved = moved.post_concat(transform);
 
-                    match event {
-                        WindowEvent::MouseInput {
-                            device_id: _,
-                            state,
-                            button,
-                        } => {
-                            // dbg!((device_id, state, button));
+        if let Some(mark) = self.annotate.as_mut().and_then(Annotate::finish) {
+            self.annotations.push(mark);
+        }
+        for mark in &mut self.annotations {
+            mark.map(transform, font);
+        }
+        if let Some(measure) = &mut self.measure {
+            *measure = Measure::default();
+        }
+        // the selection was on the pixels before the edit
+        self.crop = self.crop.as_ref().map(|_| Crop::default());
+        let edited = edit.apply(image);
+        self.clipboard = ClipboardContent::Image(image_data(edited.clone()));
+        self.image = Some(edited);
+        self.fit_content(font, config);
+    }
 
-                            let (width, height) = {
-                                let size = win.window.inner_size();
-                                (size.width as f64, size.height as f64)
-                            };
+    /// Brings back the image note as it was pasted, with the marks where they were on it
+    fn revert_image(&mut self, font: &Font, config: &Config) {
+        let Some((original, moved)) = self.original.take() else {
+            return;
+        };
 
-                            if button == MouseButton::Left && state.is_pressed() {
-                                if win.mouse_pos.x > width - 30.0 && win.mouse_pos.y < 30.0 {
-                                    // close button
-                                    win.window.set_visible(false);
-                                    windows_local.remove_elem(|e| e.window_id == window_id);
-                                } else if win.mouse_pos.x < 20.0 && win.mouse_pos.y < 20.0 {
-                                    // west north
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::NorthWest)
-                                        .unwrap();
-                                } else if win.mouse_pos.x < 20.0 && win.mouse_pos.y > height - 20.0
-                                {
-                                    // west south
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::SouthWest)
-                                        .unwrap();
-                                } else if win.mouse_pos.x > width - 20.0 && win.mouse_pos.y < 20.0 {
-                                    // east north
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::NorthEast)
-                                        .unwrap();
-                                } else if win.mouse_pos.x > width - 20.0
-                                    && win.mouse_pos.y > height - 20.0
-                                {
-                                    // east south
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::SouthEast)
-                                        .unwrap();
-                                } else if win.mouse_pos.y < 20.0 && win.mouse_pos.x < 20.0 {
-                                    // north west
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::NorthWest)
-                                        .unwrap();
-                                } else if win.mouse_pos.y < 20.0 && win.mouse_pos.x > width - 20.0 {
-                                    // north east
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::NorthEast)
-                                        .unwrap();
-                                } else if win.mouse_pos.y > height - 20.0 && win.mouse_pos.x < 20.0
-                                {
-                                    // south west
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::SouthWest)
-                                        .unwrap();
-                                } else if win.mouse_pos.y > height - 20.0
-                                    && win.mouse_pos.x > width - 20.0
-                                {
-                                    // south east
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::SouthEast)
-                                        .unwrap();
-                                } else if win.mouse_pos.x < 20.0 {
-                                    // west
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::West)
-                                        .unwrap();
-                                } else if win.mouse_pos.x > width - 20.0 {
-                                    // east
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::East)
-                                        .unwrap();
-                                } else if win.mouse_pos.y > height - 20.0 {
-                                    // south
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::South)
-                                        .unwrap();
-                                } else if win.mouse_pos.y < 20.0 {
-                                    // north
-                                    win.window
-                                        .drag_resize_window(ResizeDirection::North)
-                                        .unwrap();
-                                } else {
-                                    // else
-                                    win.window.drag_window().unwrap();
-                                }
-                            }
-                        }
-                        WindowEvent::CursorMoved {
-                            device_id: _,
-                            position,
-                        } => {
-                            win.mouse_pos = position;
+        if let Some(mark) = self.annotate.as_mut().and_then(Annotate::finish) {
+            self.annotations.push(mark);
+        }
+        if let Some(back) = moved.invert() {
+            for mark in &mut self.annotations {
+                mark.map(back, font);
+            }
+        }
+        if let Some(measure) = &mut self.measure {
+            *measure = Measure::default();
+        }
+        self.crop = self.crop.as_ref().map(|_| Crop::default());
+        self.image = Some(render_image(original.clone()));
+        self.clipboard = ClipboardContent::Image(original);
+        self.fit_content(font, config);
+    }
 
-                            let (width, height) = {
-                                let size = win.window.inner_size();
-                                (size.width as f64, size.height as f64)
-                            };
+    /// Resizes the note to the size a new one with its content would have
+    fn fit_content(&mut self, font: &Font, config: &Config) {
+        let monitor = self.window.current_monitor();
+        let size = note_size(
+            &self.clipboard,
+            font,
+            config,
+            monitor.as_ref().map(Bounds::of_monitor),
+        );
+        if self.collapsed {
+            self.expanded_size = size;
+        } else {
+            let _ = self.window.request_inner_size(size);
+        }
+    }
 
-                            if position.x > width - 30.0 && position.y < 30.0 {
-                                win.window.set_cursor_icon(CursorIcon::Pointer);
-                            } else if position.x < 20.0 || position.x > width - 20.0 {
-                                win.window.set_cursor_icon(CursorIcon::EwResize)
-                            } else if position.y < 20.0 || position.y > height - 20.0 {
-                                win.window.set_cursor_icon(CursorIcon::NsResize)
-                            } else {
-                                win.window.set_cursor_icon(CursorIcon::Pointer);
-                            }
-                        }
-                        WindowEvent::RedrawRequested => {
-                            let (width, height) = {
-                                let size = win.window.inner_size();
-                                (size.width, size.height)
-                            };
-                            win.surface
-                                .resize(
-                                    NonZeroU32::new(width).unwrap(),
-                                    NonZeroU32::new(height).unwrap(),
-                                )
-                                .unwrap();
+    /// How big image pixels of an image note are on the screen
+    fn image_zoom(&self, title_bar: bool) -> Option<f32> {
+        let (width, height) = self.image_size()?;
+        let transform = image_transform(self.content_area(title_bar)?, width, height);
+        Some(transform.sx.min(transform.sy))
+    }
 
-                            let mut pixmap = Pixmap::new(width, height).unwrap();
-                            pixmap.fill(Color::from_rgba8(250, 250, 120, 250));
-
-                            let pixmap_paint = PixmapPaint::default();
-
-                            match &win.clipboard {
-                                ClipboardContent::Text(t) => {
-                                    let win_size: (f32, f32) = (width as f32, height as f32);
-                                    let text_table_size: (f32, f32) = {
-                                        let pix = render_text_with_ln(
-                                            t.to_string(),
-                                            10.0,
-                                            &font,
-                                            12,
-                                            Color::from_rgba8(255, 0, 0, 255),
-                                        );
-                                        (pix.width() as f32 / 10.0, pix.height() as f32 / 10.0)
-                                    };
+    fn next_view(&mut self) {
+        let ClipboardContent::Text(t) = &self.clipboard else {
+            return;
+        };
+
+        self.view = self.view.next();
+        if self.view == TextView::Checklist && self.checklist.is_none() {
+            self.checklist = Some(Checklist::parse(t));
+        }
+        if self.view == TextView::Code && self.language.is_none() {
+            self.language = Some(Language::ALL[0]);
+        }
+        if self.view == TextView::Tree && self.tree.is_none() {
+            self.tree = Tree::parse(t);
+        }
+        if self.view == TextView::Table && self.table.is_none() {
+            self.table = Table::parse(t);
+        }
+        if self.view == TextView::Swatch && self.swatch.is_none() {
+            self.swatch = Swatch::parse(t);
+        }
 
-                                    // dbg!(&text_table_size);
+        // text can't be shown as data, a table, a diff, terminal output or a color unless it's one
+        let missing = match self.view {
+            TextView::Tree => self.tree.is_none(),
+            TextView::Table => self.table.is_none(),
+            TextView::Swatch => self.swatch.is_none(),
+            TextView::Terminal => !ansi::has_escapes(t),
+            TextView::Diff | TextView::SideBySide => !diff::is_diff(t),
+            _ => false,
+        };
+        if missing {
+            self.next_view();
+        }
+    }
 
-                                    let text_size = cmp::min(
-                                        (win_size.1 / text_table_size.1) as i32,
-                                        (win_size.0 / text_table_size.0) as i32,
-                                    ) as f32;
+    fn scrolls(&self) -> bool {
+        self.shown_tree().is_some() || self.shown_table().is_some()
+    }
 
-                                    let text_pixmap = render_text_with_ln(
-                                        t.to_string(),
-                                        text_size,
-                                        &font,
-                                        (text_size * 1.2) as i32,
-                                        Color::from_rgba8(255, 0, 0, 255),
-                                    );
+    fn scroll_by(&mut self, rows: i32) {
+        match self.view {
+            TextView::Tree => self.tree.iter_mut().for_each(|tree| tree.scroll_by(rows)),
+            TextView::Table => self
+                .table
+                .iter_mut()
+                .for_each(|table| table.scroll_by(rows)),
+            _ => {}
+        }
+        self.window.request_redraw();
+    }
 
-                                    let text_pos = (
-                                        width as i32 / 2 - text_pixmap.width() as i32 / 2,
-                                        height as i32 / 2 - text_pixmap.height() as i32 / 2,
-                                    );
+    /// Replaces the text with the one without escape sequences, and shows it as such text
+    fn strip_escapes(&mut self) {
+        let ClipboardContent::Text(t) = &self.clipboard else {
+            return;
+        };
+
+        let text = ansi::strip(t);
+        self.view = TextView::detect(&text);
+        self.language = Language::detect(&text);
+        self.checklist = match self.view {
+            TextView::Checklist => Some(Checklist::parse(&text)),
+            _ => None,
+        };
+        self.tree = match self.view {
+            TextView::Tree => Tree::parse(&text),
+            _ => None,
+        };
+        self.table = match self.view {
+            TextView::Table => Table::parse(&text),
+            _ => None,
+        };
+        self.swatch = match self.view {
+            TextView::Swatch => Swatch::parse(&text),
+            _ => None,
+        };
+        self.clipboard = ClipboardContent::Text(text);
+    }
 
-                                    // draw_debug_rect(
-                                    //     &mut pixmap,
-                                    //     text_pos.0 as i32,
-                                    //     text_pos.1 as i32,
-                                    //     text_pixmap.width() as i32,
-                                    //     text_pixmap.height() as i32
-                                    // );
-
-                                    pixmap.draw_pixmap(
-                                        text_pos.0,
-                                        text_pos.1,
-                                        text_pixmap.as_ref(),
-                                        &pixmap_paint,
-                                        Transform::identity(),
-                                        None,
-                                    );
-                                }
-                                ClipboardContent::Image(im) => {
-                                    let image_pixmap = render_image(im.clone());
+    fn next_language(&mut self) {
+        if let Some(language) = &mut self.language {
+            *language = language.next();
+        }
+    }
 
-                                    let win_size: (f32, f32) = (width as f32, height as f32);
-                                    let image_size: (f32, f32) =
-                                        (image_pixmap.width() as f32, image_pixmap.height() as f32);
-                                    let image_scale =
-                                        (win_size.0 / image_size.0, win_size.1 / image_size.1);
+    /// Next moment the note changes by itself, `None` while it's static
+    fn next_tick(&self, now: Instant) -> Option<Instant> {
+        [
+            self.expiry.map(|expiry| expiry.next_tick(now)),
+            match &self.clipboard {
+                ClipboardContent::Timer(timer) => timer.next_tick(now),
+                _ => None,
+            },
+            self.reminder.as_ref().map(Reminder::instant),
+            self.flash_until
+                .filter(|until| *until > now)
+                .map(|until| until.min(now + FLASH_TICK)),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
 
-                                    let image_pos: (i32, i32) = (0, 0);
+    fn is_hidden(&self) -> bool {
+        self.reminder.is_some()
+    }
 
-                                    pixmap.draw_pixmap(
-                                        image_pos.0,
-                                        image_pos.1,
-                                        image_pixmap.as_ref(),
-                                        &pixmap_paint,
-                                        Transform::from_scale(image_scale.0, image_scale.1),
-                                        None,
-                                    );
-                                }
-                                _ => {}
-                            }
+    /// Hides the note until the given time
+    fn remind(&mut self, due: DateTime<Local>, font: &Font) {
+        // a timer keeps its own time, it can't be saved and brought back later
+        if matches!(self.clipboard, ClipboardContent::Timer(_)) {
+            self.menu = None;
+            self.flash();
+            return;
+        }
+        self.reminder = Some(Reminder::schedule(due, &self.content(font)));
+        self.expiry = None;
+        self.menu = None;
+        self.redraw_at = None;
+        self.window.set_visible(false);
+    }
 
-                            let path = PathBuilder::from_rect(
-                                Rect::from_xywh(width as f32 - 30.0, 0.0, 30.0, 30.0).unwrap(),
-                            );
-
-                            let mut paint = Paint::default();
-                            paint.set_color_rgba8(220, 80, 80, 150);
-
-                            pixmap.fill_path(
-                                &path,
-                                &paint,
-                                FillRule::EvenOdd,
-                                Transform::identity(),
-                                None,
-                            );
-
-                            let mut path = PathBuilder::new();
-                            path.move_to(width as f32 - 22.5, 2.5);
-                            path.line_to(width as f32 - 2.5, 27.5);
-                            path.line_to(width as f32 - 5.0, 27.5);
-                            path.line_to(width as f32 - 25.0, 2.5);
-                            path.line_to(width as f32 - 22.5, 2.5);
-
-                            let path = path.finish().unwrap();
-
-                            let mut paint = Paint::default();
-                            paint.set_color_rgba8(220, 220, 220, 255);
-
-                            pixmap.fill_path(
-                                &path,
-                                &paint,
-                                FillRule::EvenOdd,
-                                Transform::identity(),
-                                None,
-                            );
-
-                            let mut path = PathBuilder::new();
-
-                            path.move_to(width as f32 - 5.0, 2.5);
-                            path.line_to(width as f32 - 25.0, 27.5);
-                            path.line_to(width as f32 - 22.5, 27.5);
-                            path.line_to(width as f32 - 2.5, 2.5);
-                            path.line_to(width as f32 - 5.0, 2.5);
-
-                            let path = path.finish().unwrap();
-
-                            pixmap.fill_path(
-                                &path,
-                                &paint,
-                                FillRule::EvenOdd,
-                                Transform::identity(),
-                                None,
-                            );
+    /// Brings back the note of a due reminder
+    fn wake_up(&mut self) {
+        if let Some(reminder) = self.reminder.take() {
+            reminder.forget();
+        }
 
-                            let mut buffer = win.surface.buffer_mut().unwrap();
-                            for index in 0..(width * height) as usize {
-                                buffer[index] = pixmap.data()[index * 4 + 2] as u32
-                                    | (pixmap.data()[index * 4 + 1] as u32) << 8
-                                    | (pixmap.data()[index * 4] as u32) << 16;
-                            }
+        self.window.set_visible(true);
+        self.set_level(WindowLevel::AlwaysOnTop);
+        self.window.focus_window();
+        self.flash();
+    }
 
-                            buffer.present().unwrap();
-                        }
-                        _ => (),
-                    }
-                }
-                _ => (),
-            }
+    fn flash(&mut self) {
+        self.flash_until = Some(Instant::now() + FLASH_TIME);
+        self.redraw_at = None;
+    }
+
+    /// Whether the flashing border is shown right now
+    fn flash_on(&self, now: Instant) -> bool {
+        self.flash_until.is_some_and(|until| {
+            until > now && ((until - now).as_millis() / FLASH_TICK.as_millis()).is_multiple_of(2)
         })
-        .unwrap();
-}
+    }
 
-#[derive(Debug)]
-struct MyUserEvent {
-    window_builder: WindowBuilder,
-    clipboard: ClipboardContent,
-}
+    fn toggle_expiry(&mut self, ttl: Duration) {
+        if self.expiry.take().is_none() {
+            self.expiry = Some(Expiry::new(ttl));
+        }
+        self.redraw_at = None;
+    }
 
-fn popup_clipboard(content: ClipboardContent) -> MyUserEvent {
-    MyUserEvent {
-        window_builder: WindowBuilder::new()
-            .with_enabled_buttons(WindowButtons::empty())
-            .with_decorations(false)
-            .with_window_level(WindowLevel::AlwaysOnTop)
-            .with_title(
-                "FONotes - ".to_owned()
-                    + (match content {
-                        ClipboardContent::Image(_) => "Image",
-                        ClipboardContent::Text(_) => "Text",
-                        _ => "???",
-                    }),
-            )
-            .with_inner_size(match &content {
-                ClipboardContent::Image(i) => LogicalSize::new(i.width as f32, i.height as f32),
-                _ => LogicalSize::new(250.0, 300.0),
-            })
-            .with_resizable(true)
-            .with_visible(true)
-            .with_min_inner_size(LogicalSize::new(50.0, 50.0)),
-        clipboard: content,
+    /// Collapsed notes always get the title strip, it is all that is left of them
+    fn has_title_bar(&self, title_bar: bool) -> bool {
+        title_bar || self.collapsed
     }
-}
 
-fn main() {
-    let event_loop = create_event_loop();
-    let windows = RefCell::new(Vec::new());
+    /// Height the help is drawn in, under the title bar
+    fn help_height(&self, title_bar: bool) -> f32 {
+        self.content_area(title_bar)
+            .map_or(0.0, |area| area.height())
+    }
 
-    let event_loop_proxy = event_loop.create_proxy();
+    fn scroll_help(&mut self, rows: i32, title_bar: bool) {
+        self.help_scroll = help::scroll(self.help_scroll, rows, self.help_height(title_bar));
+        self.window.request_redraw();
+    }
 
-    thread::spawn(move || {
-        let mut pressed: Vec<Key> = Vec::new();
-        let mut clipboard = Clipboard::new().unwrap();
+    /// Registers a click on the note, returns true if it completes a double click
+    fn double_click(&mut self) -> bool {
+        let now = Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|last| now.duration_since(last) < DOUBLE_CLICK_TIME);
 
-        listen(move |event: rdev::Event| {
-            if let EventType::KeyPress(key) = event.event_type {
-                if !pressed.contains(&key) {
-                    pressed.push(key);
-                }
+        self.last_click = if double { None } else { Some(now) };
+        double
+    }
 
-                if key == Key::KeyN
+    /// One line describing the content
+    fn preview(&self) -> String {
+        match &self.clipboard {
+            ClipboardContent::Text(t) => t
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default()
+                .to_string(),
+            ClipboardContent::Image(i) => format!("{}x{}", i.width, i.height),
+            ClipboardContent::Timer(timer) => timer.text(Instant::now()),
+            ClipboardContent::None => String::new(),
+        }
+    }
+
+    /// Area under the title bar the content is drawn in
+    fn content_area(&self, title_bar: bool) -> Option<Rect> {
+        let size = self.window.inner_size();
+        let top = if self.has_title_bar(title_bar) {
+            TITLE_BAR_HEIGHT
+        } else {
+            0.0
+        };
+        Rect::from_xywh(0.0, top, size.width as f32, size.height as f32 - top)
+    }
+
+    fn title(&self) -> String {
+        let time = match self.expiry {
+            Some(expiry) => format!(
+                "{} · closes in {}",
+                self.created.format("%H:%M"),
+                expiry::format_duration(expiry.remaining(Instant::now()))
+            ),
+            None => self.created.format("%H:%M").to_string(),
+        };
+
+        // the strip of a collapsed note is there to recognize it, so the preview goes first
+        if self.collapsed {
+            return format!("{} · {}", self.preview(), time);
+        }
+
+        let kind = match self.shown_checklist() {
+            Some(checklist) => {
+                let (done, total) = checklist.progress();
+                format!("Checklist {done}/{total}")
+            }
+            None if self.shown_table().is_some() => {
+                let (rows, columns) = self.shown_table().unwrap().size();
+                format!("Table {rows}×{columns}")
+            }
+            None if self.view == TextView::Code => {
+                self.language.map_or("Code", Language::name).to_string()
+            }
+            None if self.view != TextView::Text => self.view.name().to_string(),
+            None => self.clipboard.kind().to_string(),
+        };
+
+        format!("{} · {} · {}", kind, time, self.preview())
+    }
+
+    fn hit_test(&self, title_bar: bool, font: &Font) -> Hit {
+        let title_bar = self.has_title_bar(title_bar);
+        let (width, height) = {
+            let size = self.window.inner_size();
+            (size.width as f64, size.height as f64)
+        };
+        let (x, y) = (self.mouse_pos.x, self.mouse_pos.y);
+
+        if let Some(menu) = &self.menu {
+            return Hit::Menu(menu.item_at(
+                self.menu_items().len(),
+                width as f32,
+                height as f32,
+                x as f32,
+                y as f32,
+            ));
+        }
+
+        for (button, rect) in title_bar::layout(width as f32, title_bar) {
+            if x >= rect.left() as f64
+                && x < rect.right() as f64
+                && y >= rect.top() as f64
+                && y < rect.bottom() as f64
+            {
+                return Hit::Button(button);
+            }
+        }
+
+        if self.collapsed {
+            return Hit::Drag;
+        }
+
+        if let (ClipboardContent::Timer(_), Some(area)) =
+            (&self.clipboard, self.content_area(title_bar))
+        {
+            for (button, rect) in timer::layout(area) {
+                if rect_contains(rect, x as f32, y as f32) {
+                    return Hit::Timer(button);
+                }
+            }
+        }
+
+        // the title bar is the place to grab the note, so its top border is thin
+        let north_border = if title_bar { 4.0 } else { RESIZE_BORDER };
+
+        let west = x < RESIZE_BORDER;
+        let east = x > width - RESIZE_BORDER;
+        let north = y < north_border;
+        let south = y > height - RESIZE_BORDER;
+
+        match (north, south, west, east) {
+            (true, _, true, _) => Hit::Resize(ResizeDirection::NorthWest),
+            (true, _, _, true) => Hit::Resize(ResizeDirection::NorthEast),
+            (_, true, true, _) => Hit::Resize(ResizeDirection::SouthWest),
+            (_, true, _, true) => Hit::Resize(ResizeDirection::SouthEast),
+            (true, _, _, _) => Hit::Resize(ResizeDirection::North),
+            (_, true, _, _) => Hit::Resize(ResizeDirection::South),
+            (_, _, true, _) => Hit::Resize(ResizeDirection::West),
+            (_, _, _, true) => Hit::Resize(ResizeDirection::East),
+            // items of a checklist, rows of a tree or a swatch and table cells are clicked,
+            // the rest of the note is grabbed
+            _ => self
+                .shown_checklist()
+                .zip(self.content_area(title_bar))
+                .and_then(|(checklist, area)| {
+                    checklist
+                        .layout(area, font)
+                        .iter()
+                        .position(|row| rect_contains(*row, x as f32, y as f32))
+                })
+                .map(Hit::ChecklistItem)
+                .or_else(|| {
+                    let area = self.content_area(title_bar)?;
+                    let row = self.shown_tree()?.row_at(area, x as f32, y as f32)?;
+                    Some(Hit::TreeRow(row))
+                })
+                .or_else(|| {
+                    let area = self.content_area(title_bar)?;
+                    let table = self.shown_table()?;
+                    let (x, y) = (x as f32, y as f32);
+                    match table.edge_at(area, font, x, y) {
+                        Some(column) => Some(Hit::TableEdge(column)),
+                        None => table
+                            .cell_at(area, font, x, y)
+                            .map(|(row, column)| Hit::TableCell(row, column)),
+                    }
+                })
+                .or_else(|| {
+                    self.eyedropper.as_ref()?;
+                    let area = self.content_area(title_bar)?;
+                    rect_contains(area, x as f32, y as f32).then_some(Hit::Pixel)
+                })
+                .or_else(|| {
+                    self.measure.as_ref()?;
+                    let area = self.content_area(title_bar)?;
+                    rect_contains(area, x as f32, y as f32).then_some(Hit::Measure)
+                })
+                .or_else(|| {
+                    self.annotate.as_ref()?;
+                    let area = self.content_area(title_bar)?;
+                    rect_contains(area, x as f32, y as f32).then_some(Hit::Annotate)
+                })
+                .or_else(|| {
+                    self.crop.as_ref()?;
+                    let area = self.content_area(title_bar)?;
+                    rect_contains(area, x as f32, y as f32).then_some(Hit::Crop)
+                })
+                .or_else(|| {
+                    let area = self.content_area(title_bar)?;
+                    let row = self.shown_swatch()?.row_at(area, x as f32, y as f32)?;
+                    Some(Hit::SwatchRow(row))
+                })
+                .unwrap_or(Hit::Drag),
+        }
+    }
+
+    fn menu_items(&self) -> Vec<(Action, String)> {
+        let page = self.menu.as_ref().map_or(MenuPage::Main, |menu| menu.page);
+        menu::items(
+            &MenuState {
+                level: self.level,
+                color_tag: self.color_tag,
+                opacity: self.opacity,
+                collapsed: self.collapsed,
+                expires_in: self.expiry.map(|e| e.remaining(Instant::now())),
+                timer_running: match &self.clipboard {
+                    ClipboardContent::Timer(timer) => Some(timer.is_running()),
+                    _ => None,
+                },
+                view: match &self.clipboard {
+                    ClipboardContent::Text(_) => Some(self.view),
+                    _ => None,
+                },
+                language: self.language.filter(|_| self.view == TextView::Code),
+                eyedropper: match &self.clipboard {
+                    ClipboardContent::Image(_) => Some(self.eyedropper.is_some()),
+                    _ => None,
+                },
+                measuring: match &self.clipboard {
+                    ClipboardContent::Image(_) => Some(self.measure.is_some()),
+                    _ => None,
+                },
+                annotate: self.annotate.as_ref().map(|a| (a.tool, a.color_name())),
+                annotating: match &self.clipboard {
+                    ClipboardContent::Image(_) => Some(self.annotate.is_some()),
+                    _ => None,
+                },
+                marks: self.annotations.len(),
+                cropping: match &self.clipboard {
+                    ClipboardContent::Image(_) => Some(self.crop.is_some()),
+                    _ => None,
+                },
+                selection: self.crop.as_ref().is_some_and(|c| c.selection().is_some()),
+                edited: self.original.is_some(),
+                escapes: matches!(&self.clipboard, ClipboardContent::Text(t) if ansi::has_escapes(t)),
+            },
+            page,
+        )
+    }
+
+    fn open_menu(&mut self, x: f32, y: f32) {
+        self.menu = Some(ContextMenu::new(x, y));
+        self.hovered = None;
+        self.window.request_redraw();
+    }
+
+    fn close_menu(&mut self) {
+        self.menu = None;
+        self.window.request_redraw();
+    }
+
+    /// Does the action, returns false when the note was closed
+    fn perform(
+        &mut self,
+        action: Action,
+        clipboard: &mut Clipboard,
+        proxy: &EventLoopProxy<MyUserEvent>,
+        config: &Config,
+        font: &Font,
+    ) -> bool {
+        match action {
+            Action::Copy => self.copy_to(clipboard, font),
+            Action::Export => self.export(&config.export_dir, font),
+            Action::ChangeColor => self.next_color_tag(),
+            Action::ChangeLevel => self.set_level(next_level(self.level)),
+            Action::OpacityUp => self.change_opacity(OPACITY_STEP),
+            Action::OpacityDown => self.change_opacity(-OPACITY_STEP),
+            Action::Duplicate => {
+                proxy
+                    .send_event(popup_clipboard(self.content(font), None, None, None))
+                    .unwrap();
+            }
+            Action::Collapse => self.toggle_collapse(),
+            Action::Help => {
+                self.show_help = !self.show_help;
+                self.help_scroll = 0;
+            }
+            Action::ToggleExpiry => self.toggle_expiry(config.ttl),
+            Action::RemindMenu => {
+                if let Some(menu) = &mut self.menu {
+                    menu.open_page(MenuPage::Remind);
+                }
+            }
+            Action::MenuBack => {
+                if let Some(menu) = &mut self.menu {
+                    menu.open_page(MenuPage::Main);
+                }
+            }
+            Action::Remind(time) => self.remind(time.due(), font),
+            Action::NextView => self.next_view(),
+            Action::NextLanguage => self.next_language(),
+            Action::CheckItem(index) => {
+                if let Some(checklist) = &mut self.checklist {
+                    checklist.toggle(index);
+                }
+            }
+            Action::TreeRow(index) => {
+                let copied = self.tree.as_mut().and_then(|tree| tree.click(index));
+                if let Some(text) = copied {
+                    if let Err(e) = clipboard.set_text(text) {
+                        eprintln!("can't copy value to clipboard: {e}");
+                    }
+                }
+            }
+            Action::CopyCell(row, column) => {
+                let copied = self
+                    .table
+                    .as_mut()
+                    .and_then(|table| table.click(row, column));
+                if let Some(text) = copied {
+                    if let Err(e) = clipboard.set_text(text) {
+                        eprintln!("can't copy cell to clipboard: {e}");
+                    }
+                }
+            }
+            Action::StripEscapes => self.strip_escapes(),
+            // one tool at a time, the clicks would mean two things otherwise
+            Action::Eyedropper => {
+                self.eyedropper = match (&self.clipboard, &self.eyedropper) {
+                    (ClipboardContent::Image(_), None) => Some(Eyedropper::default()),
+                    _ => None,
+                };
+                self.measure = None;
+                self.stop_annotating();
+                self.crop = None;
+            }
+            Action::Measure => {
+                self.measure = match (&self.clipboard, &self.measure) {
+                    (ClipboardContent::Image(_), None) => Some(Measure::default()),
+                    _ => None,
+                };
+                self.eyedropper = None;
+                self.stop_annotating();
+                self.crop = None;
+            }
+            Action::Annotate => {
+                let annotating = self.annotate.is_some();
+                self.stop_annotating();
+                if !annotating && matches!(self.clipboard, ClipboardContent::Image(_)) {
+                    self.annotate = Some(Annotate::default());
+                }
+                self.eyedropper = None;
+                self.measure = None;
+                self.crop = None;
+            }
+            Action::Crop => {
+                self.crop = match (&self.clipboard, &self.crop) {
+                    (ClipboardContent::Image(_), None) => Some(Crop::default()),
+                    _ => None,
+                };
+                self.eyedropper = None;
+                self.measure = None;
+                self.stop_annotating();
+            }
+            Action::ApplyCrop => {
+                if let Some(selection) = self.crop.take().and_then(|c| c.selection()) {
+                    self.edit_image(Edit::Crop(selection), font, config);
+                }
+            }
+            Action::Rotate => self.edit_image(Edit::Rotate, font, config),
+            Action::FlipHorizontal => self.edit_image(Edit::FlipHorizontal, font, config),
+            Action::FlipVertical => self.edit_image(Edit::FlipVertical, font, config),
+            Action::RevertImage => self.revert_image(font, config),
+            Action::NextTool => {
+                if let Some(annotate) = &mut self.annotate {
+                    annotate.tool = annotate.tool.next();
+                }
+            }
+            Action::NextMarkColor => {
+                if let Some(annotate) = &mut self.annotate {
+                    annotate.next_color();
+                }
+            }
+            Action::Undo => {
+                // a label being typed goes first, it's the latest mark
+                match &mut self.annotate {
+                    Some(annotate) if annotate.typing() => annotate.cancel(),
+                    _ => {
+                        self.annotations.pop();
+                    }
+                }
+            }
+            Action::PickColor => {
+                let area = self.content_area(config.title_bar);
+                let picked = match (&self.image, &mut self.eyedropper, area) {
+                    (Some(image), Some(eyedropper), Some(area)) => eyedropper.pick(image, area),
+                    _ => None,
+                };
+                if let Some(hex) = picked {
+                    if let Err(e) = clipboard.set_text(hex) {
+                        eprintln!("can't copy color to clipboard: {e}");
+                    }
+                }
+            }
+            Action::CopyFormat(index) => {
+                let copied = self.swatch.as_mut().and_then(|swatch| swatch.click(index));
+                if let Some(text) = copied {
+                    if let Err(e) = clipboard.set_text(text) {
+                        eprintln!("can't copy color to clipboard: {e}");
+                    }
+                }
+            }
+            Action::TimerStartPause | Action::TimerReset => {
+                if let ClipboardContent::Timer(timer) = &mut self.clipboard {
+                    if action == Action::TimerReset {
+                        timer.reset();
+                    } else {
+                        timer.toggle();
+                    }
+                }
+                self.flash_until = None;
+                // the redraw schedule changes with the timer
+                self.redraw_at = None;
+            }
+            Action::ArrangeAll => {
+                proxy
+                    .send_event(MyUserEvent::ArrangeAll(self.window.current_monitor()))
+                    .unwrap();
+            }
+            Action::Close => {
+                self.window.set_visible(false);
+                return false;
+            }
+        }
+
+        if !action.keeps_menu() {
+            self.menu = None;
+        }
+        let count = self.menu_items().len();
+        let height = self.window.inner_size().height as f32;
+        if let Some(menu) = &mut self.menu {
+            menu.clamp(count, height);
+        }
+        self.window.request_redraw();
+
+        true
+    }
+
+    fn export(&self, dir: &Path, font: &Font) {
+        let name = format!("fonotes-{}", Local::now().format("%Y%m%d-%H%M%S"));
+
+        let result = match &self.content(font) {
+            ClipboardContent::Text(t) => {
+                fs::write(dir.join(name + ".txt"), t).map_err(|e| e.to_string())
+            }
+            ClipboardContent::Image(i) => render_image(i.clone())
+                .save_png(dir.join(name + ".png"))
+                .map_err(|e| e.to_string()),
+            ClipboardContent::Timer(timer) => {
+                fs::write(dir.join(name + ".txt"), timer.text(Instant::now()))
+                    .map_err(|e| e.to_string())
+            }
+            ClipboardContent::None => Ok(()),
+        };
+
+        if let Err(e) = result {
+            eprintln!("can't export note: {e}");
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let position = self.window.outer_position().ok()?;
+        Some(Bounds::new(position, self.window.outer_size()))
+    }
+
+    fn set_bounds(&mut self, bounds: Bounds) {
+        let size = PhysicalSize::new(bounds.width as u32, bounds.height as u32);
+
+        self.window
+            .set_outer_position(PhysicalPosition::new(bounds.x, bounds.y));
+        if self.collapsed {
+            self.expanded_size = size;
+        } else {
+            let _ = self.window.request_inner_size(size);
+        }
+    }
+
+    fn move_by(&mut self, dx: i32, dy: i32) {
+        let Ok(position) = self.window.outer_position() else {
+            return;
+        };
+
+        self.skip_snap = true;
+        self.window
+            .set_outer_position(PhysicalPosition::new(position.x + dx, position.y + dy));
+    }
+
+    fn resize_by(&mut self, dx: i32, dy: i32) {
+        if self.collapsed {
+            return;
+        }
+
+        let size = self.window.inner_size();
+        let width = (size.width as i32 + dx).max(MIN_NOTE_SIZE.width as i32);
+        let height = (size.height as i32 + dy).max(MIN_NOTE_SIZE.height as i32);
+        let _ = self
+            .window
+            .request_inner_size(PhysicalSize::new(width as u32, height as u32));
+    }
+
+    fn set_level(&mut self, level: WindowLevel) {
+        self.level = level;
+        self.window.set_window_level(level);
+        self.window.request_redraw();
+    }
+
+    fn copy_to(&self, clipboard: &mut Clipboard, font: &Font) {
+        let result = match &self.content(font) {
+            ClipboardContent::Text(t) => clipboard.set_text(t.clone()),
+            ClipboardContent::Image(i) => clipboard.set_image(i.clone()),
+            ClipboardContent::Timer(timer) => clipboard.set_text(timer.text(Instant::now())),
+            ClipboardContent::None => Ok(()),
+        };
+
+        if let Err(e) = result {
+            eprintln!("can't copy note to clipboard: {e}");
+        }
+    }
+
+    fn toggle_collapse(&mut self) {
+        if self.collapsed {
+            self.window.set_min_inner_size(Some(MIN_NOTE_SIZE));
+            let _ = self.window.request_inner_size(self.expanded_size);
+        } else {
+            self.expanded_size = self.window.inner_size();
+            let strip = PhysicalSize::new(self.expanded_size.width, TITLE_BAR_HEIGHT as u32);
+            self.window.set_min_inner_size(Some(PhysicalSize::new(
+                MIN_NOTE_SIZE.width as u32,
+                strip.height,
+            )));
+            let _ = self.window.request_inner_size(strip);
+        }
+
+        self.collapsed = !self.collapsed;
+        self.window.request_redraw();
+    }
+
+    fn change_opacity(&mut self, delta: f32) {
+        self.opacity = (self.opacity + delta).clamp(MIN_OPACITY, 1.0);
+        self.window.request_redraw();
+    }
+
+    fn set_click_through(&mut self, click_through: bool) {
+        match self.window.set_cursor_hittest(!click_through) {
+            Ok(()) => self.click_through = click_through,
+            Err(e) => eprintln!("can't change click-through: {e}"),
+        }
+    }
+
+    fn next_color_tag(&mut self) {
+        self.color_tag = self.color_tag.next();
+        self.window.request_redraw();
+    }
+}
+
+/// Always on top, then normal, then always on bottom like a desktop widget
+fn next_level(level: WindowLevel) -> WindowLevel {
+    match level {
+        WindowLevel::AlwaysOnTop => WindowLevel::Normal,
+        WindowLevel::Normal => WindowLevel::AlwaysOnBottom,
+        WindowLevel::AlwaysOnBottom => WindowLevel::AlwaysOnTop,
+    }
+}
+
+fn create_event_loop() -> EventLoop<MyUserEvent> {
+    let event_loop: EventLoop<MyUserEvent> = EventLoopBuilder::with_user_event()
+        .with_any_thread(true)
+        .build()
+        .unwrap();
+
+    event_loop.set_control_flow(ControlFlow::Poll);
+    event_loop.set_control_flow(ControlFlow::Wait);
+
+    event_loop
+}
+
+fn get_window(mut windows: IterMut<'_, Note>, id: WindowId) -> Option<&mut Note> {
+    windows.find(|note| note.window_id == id)
+}
+
+/// Initial note size that fits the content, but takes at most a part of the monitor
+fn note_size(
+    content: &ClipboardContent,
+    font: &Font,
+    config: &Config,
+    monitor: Option<Bounds>,
+) -> PhysicalSize<u32> {
+    let title_bar = if config.title_bar {
+        TITLE_BAR_HEIGHT
+    } else {
+        0.0
+    };
+    let (max_width, max_height) = match monitor {
+        Some(m) => (
+            m.width as f32 * MAX_SCREEN_FRACTION,
+            m.height as f32 * MAX_SCREEN_FRACTION - title_bar,
+        ),
+        None => (f32::MAX, f32::MAX),
+    };
+
+    let (width, height) = match content {
+        ClipboardContent::Text(t) if Swatch::detect(t) => swatch::SIZE,
+        ClipboardContent::Text(t) => {
+            let (width, height) = measure_text(t, config.font_size, font, config.font_size * 1.2);
+            let padding = RESIZE_BORDER as f32 * 2.0;
+            (
+                (width + padding).min(max_width),
+                (height + padding).min(max_height),
+            )
+        }
+        ClipboardContent::Image(i) => {
+            let (width, height) = (i.width as f32, i.height as f32);
+            let scale = (max_width / width).min(max_height / height).min(1.0);
+            (width * scale, height * scale)
+        }
+        ClipboardContent::Timer(timer) => {
+            let digits = text_width(&timer.text(Instant::now()), config.font_size * 3.0, font);
+            (
+                digits + RESIZE_BORDER as f32 * 2.0,
+                config.font_size * 4.0 + timer::CONTROLS_HEIGHT,
+            )
+        }
+        ClipboardContent::None => (250.0, 300.0),
+    };
+
+    PhysicalSize::new(
+        width.max(MIN_NOTE_SIZE.width as f32) as u32,
+        (height + title_bar).max(MIN_NOTE_SIZE.height as f32) as u32,
+    )
+}
+
+fn draw_clipboard(
+    pixmap: &mut Pixmap,
+    area: Rect,
+    content: &ClipboardContent,
+    font: &Font,
+    theme: &Theme,
+) {
+    let pixmap_paint = PixmapPaint::default();
+
+    match content {
+        ClipboardContent::Text(t) => {
+            let text_table_size: (f32, f32) = {
+                let pix = render_text_with_ln(t.to_string(), 10.0, font, 12, theme.text);
+                (pix.width() as f32 / 10.0, pix.height() as f32 / 10.0)
+            };
+
+            let text_size = cmp::min(
+                (area.height() / text_table_size.1) as i32,
+                (area.width() / text_table_size.0) as i32,
+            ) as f32;
+
+            let text_pixmap = render_text_with_ln(
+                t.to_string(),
+                text_size,
+                font,
+                (text_size * 1.2) as i32,
+                theme.text,
+            );
+
+            let text_pos = (
+                (area.x() + area.width() / 2.0) as i32 - text_pixmap.width() as i32 / 2,
+                (area.y() + area.height() / 2.0) as i32 - text_pixmap.height() as i32 / 2,
+            );
+
+            pixmap.draw_pixmap(
+                text_pos.0,
+                text_pos.1,
+                text_pixmap.as_ref(),
+                &pixmap_paint,
+                Transform::identity(),
+                None,
+            );
+        }
+        ClipboardContent::Timer(timer) => timer::draw(pixmap, area, timer, font, theme),
+        _ => {}
+    }
+}
+
+/// Draws the image stretched over the content area
+fn draw_image(pixmap: &mut Pixmap, area: Rect, image: &Pixmap) {
+    pixmap.draw_pixmap(
+        0,
+        0,
+        image.as_ref(),
+        &PixmapPaint::default(),
+        image_transform(area, image.width(), image.height()),
+        None,
+    );
+}
+
+fn run_event_loop(event_loop: EventLoop<MyUserEvent>, windows: RefCell<Vec<Note>>, config: Config) {
+    let font: Font = Font::from_bytes(
+        include_bytes!("../resources/Roboto.ttf") as &[u8],
+        FontSettings::default(),
+    )
+    .unwrap();
+    let mono: Font = Font::from_bytes(
+        include_bytes!("../resources/DejaVuSansMono.ttf") as &[u8],
+        FontSettings::default(),
+    )
+    .unwrap();
+    let theme: Theme = config.theme;
+    let title_bar = config.title_bar;
+    let mut clipboard = Clipboard::new().unwrap();
+    let proxy = event_loop.create_proxy();
+    // last position of a note with each kind of content, for `Placement::Remember`
+    let mut remembered: HashMap<&'static str, (i32, i32)> = HashMap::new();
+
+    event_loop
+        .run(move |event, elwt| {
+            let mut windows_local = windows.borrow_mut();
+
+            match event {
+                Event::Resumed => {}
+                Event::AboutToWait => {
+                    let now = Instant::now();
+
+                    windows_local.retain(|note| {
+                        let expired = note.expiry.is_some_and(|e| e.is_expired(now));
+                        if expired {
+                            note.window.set_visible(false);
+                        }
+                        !expired
+                    });
+
+                    for note in windows_local.iter_mut() {
+                        if note.reminder.as_ref().is_some_and(Reminder::is_due) {
+                            note.wake_up();
+                        }
+                        if let ClipboardContent::Timer(timer) = &mut note.clipboard {
+                            if timer.ring(now) {
+                                note.flash();
+                            }
+                        }
+                    }
+
+                    let mut wake_up: Option<Instant> = None;
+                    for note in windows_local.iter_mut() {
+                        if note.redraw_at.is_some_and(|at| at <= now) {
+                            note.window.request_redraw();
+                            note.redraw_at = None;
+                        }
+                        if note.redraw_at.is_none() {
+                            note.redraw_at = note.next_tick(now);
+                        }
+                        if let Some(at) = note.redraw_at {
+                            wake_up = Some(wake_up.map_or(at, |wake_up| wake_up.min(at)));
+                        }
+                    }
+
+                    elwt.set_control_flow(match wake_up {
+                        Some(at) => ControlFlow::WaitUntil(at),
+                        None => ControlFlow::Wait,
+                    });
+                }
+                Event::UserEvent(MyUserEvent::Popup {
+                    window_builder,
+                    clipboard,
+                    cursor,
+                    ttl,
+                    reminder,
+                }) => {
+                    let monitors: Vec<Bounds> = elwt
+                        .available_monitors()
+                        .map(|m| Bounds::of_monitor(&m))
+                        .collect();
+                    let context = PlacementContext {
+                        cursor: cursor.map(|c| (c.x as i32, c.y as i32)),
+                        last_note: windows_local
+                            .iter()
+                            .rev()
+                            .find(|note| !note.is_hidden())
+                            .and_then(Note::bounds),
+                        remembered: remembered.get(clipboard.kind()).copied(),
+                        monitors: &monitors,
+                    };
+
+                    let size = note_size(&clipboard, &font, &config, context.active_monitor());
+                    let built = window_builder.with_inner_size(size).build(elwt).unwrap();
+
+                    let size = built.outer_size();
+                    let (x, y) = placement::place(
+                        config.placement,
+                        size.width as i32,
+                        size.height as i32,
+                        &context,
+                    );
+                    built.set_outer_position(PhysicalPosition::new(x, y));
+                    if reminder.is_none() {
+                        built.set_visible(true);
+                    }
+
+                    let mut win = Note::new(built, clipboard);
+                    win.expiry = ttl.map(Expiry::new);
+                    if let (ClipboardContent::Text(_), Some(language)) =
+                        (&win.clipboard, config.language)
+                    {
+                        win.view = TextView::Code;
+                        win.language = Some(language);
+                    }
+                    win.reminder = reminder;
+                    windows_local.push(win);
+                }
+                Event::UserEvent(MyUserEvent::LeaveClickThrough) => {
+                    for note in windows_local.iter_mut() {
+                        note.set_click_through(false);
+                    }
+                }
+                Event::UserEvent(MyUserEvent::ArrangeAll(monitor)) => {
+                    let Some(monitor) = monitor
+                        .or_else(|| elwt.primary_monitor())
+                        .or_else(|| elwt.available_monitors().next())
+                    else {
+                        return;
+                    };
+
+                    let mut notes: Vec<&mut Note> = windows_local
+                        .iter_mut()
+                        .filter(|note| !note.is_hidden())
+                        .collect();
+                    let cells = snap::arrange(notes.len(), Bounds::of_monitor(&monitor));
+                    for (note, cell) in notes.iter_mut().zip(cells) {
+                        note.set_bounds(cell);
+                    }
+                }
+                Event::UserEvent(MyUserEvent::CycleFocus { from, backwards }) => {
+                    let visible: Vec<&Note> =
+                        windows_local.iter().filter(|e| !e.is_hidden()).collect();
+                    let Some(index) = visible.iter().position(|e| e.window_id == from) else {
+                        return;
+                    };
+
+                    let count = visible.len();
+                    let next = if backwards {
+                        (index + count - 1) % count
+                    } else {
+                        (index + 1) % count
+                    };
+                    visible[next].window.focus_window();
+                }
+                Event::UserEvent(MyUserEvent::ListReminders) => {
+                    let list = reminder_list(windows_local.iter().filter_map(|note| {
+                        note.reminder
+                            .as_ref()
+                            .map(|reminder| (reminder.due, note.preview()))
+                    }));
+                    proxy
+                        .send_event(popup_clipboard(
+                            ClipboardContent::Text(list),
+                            None,
+                            None,
+                            None,
+                        ))
+                        .unwrap();
+                }
+                Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::Moved(position),
+                } => {
+                    let others: Vec<Bounds> = windows_local
+                        .iter()
+                        .filter(|e| e.window_id != window_id && !e.is_hidden())
+                        .filter_map(|e| e.bounds())
+                        .collect();
+
+                    let Some(win) = get_window(windows_local.iter_mut(), window_id) else {
+                        return;
+                    };
+                    remembered.insert(win.clipboard.kind(), (position.x, position.y));
+
+                    if mem::take(&mut win.skip_snap) || config.snap_distance <= 0 {
+                        return;
+                    }
+                    let Some(monitor) = win.window.current_monitor() else {
+                        return;
+                    };
+
+                    let bounds = Bounds::new(position, win.window.outer_size());
+
+                    let snapped = snap::snap(
+                        bounds,
+                        Bounds::of_monitor(&monitor),
+                        &others,
+                        config.snap_distance,
+                    );
+                    if snapped != (bounds.x, bounds.y) {
+                        win.window
+                            .set_outer_position(PhysicalPosition::new(snapped.0, snapped.1));
+                    }
+                }
+                Event::WindowEvent { window_id, event } => {
+                    let win = match get_window(windows_local.iter_mut(), window_id) {
+                        Some(i) => i,
+                        None => {
+                            return;
+                        }
+                    };
+
+                    match event {
+                        WindowEvent::ModifiersChanged(modifiers) => {
+                            win.modifiers = modifiers.state();
+                        }
+                        WindowEvent::MouseWheel { delta, .. } if win.menu.is_some() => {
+                            let lines = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+                            };
+                            let count = win.menu_items().len();
+                            let height = win.window.inner_size().height as f32;
+                            if let Some(menu) = &mut win.menu {
+                                menu.scroll_by(-lines.round() as i32, count, height);
+                            }
+                            win.window.request_redraw();
+                        }
+                        WindowEvent::MouseWheel { delta, .. } if win.show_help => {
+                            let lines = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y * 3.0,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+                            };
+                            win.scroll_help(-lines.round() as i32, title_bar);
+                        }
+                        WindowEvent::MouseWheel { delta, .. } if win.modifiers.control_key() => {
+                            let lines = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+                            };
+                            win.change_opacity(lines * OPACITY_STEP);
+                        }
+                        WindowEvent::MouseWheel { delta, .. } if win.scrolls() => {
+                            let lines = match delta {
+                                MouseScrollDelta::LineDelta(_, y) => y * 3.0,
+                                MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / 20.0,
+                            };
+                            win.scroll_by(-lines.round() as i32);
+                        }
+                        WindowEvent::KeyboardInput { event, .. } => {
+                            if event.state != ElementState::Pressed {
+                                return;
+                            }
+
+                            let items = win.menu_items();
+                            let height = win.window.inner_size().height as f32;
+                            if let Some(menu) = &mut win.menu {
+                                menu.clamp(items.len(), height);
+                                let action = match event.logical_key.as_ref() {
+                                    keyboard::Key::Named(NamedKey::ArrowDown) => {
+                                        menu.select_next(items.len(), height);
+                                        None
+                                    }
+                                    keyboard::Key::Named(NamedKey::ArrowUp) => {
+                                        menu.select_previous(items.len(), height);
+                                        None
+                                    }
+                                    keyboard::Key::Named(NamedKey::Enter | NamedKey::Space) => {
+                                        items.get(menu.selected).map(|(action, _)| action).copied()
+                                    }
+                                    keyboard::Key::Named(NamedKey::Escape) => {
+                                        win.menu = None;
+                                        None
+                                    }
+                                    _ => None,
+                                };
+                                win.window.request_redraw();
+
+                                if let Some(action) = action {
+                                    if !win.perform(action, &mut clipboard, &proxy, &config, &font)
+                                    {
+                                        windows_local.remove_elem(|e| e.window_id == window_id);
+                                    }
+                                }
+                                return;
+                            }
+
+                            // keys go to the label being typed on an image note
+                            if let Some(annotate) = win.annotate.as_mut().filter(|a| a.typing()) {
+                                match event.logical_key.as_ref() {
+                                    keyboard::Key::Named(NamedKey::Enter) => {
+                                        if let Some(mark) = annotate.finish() {
+                                            win.annotations.push(mark);
+                                        }
+                                    }
+                                    keyboard::Key::Named(NamedKey::Escape) => annotate.cancel(),
+                                    keyboard::Key::Named(NamedKey::Backspace) => {
+                                        annotate.backspace()
+                                    }
+                                    _ if win.modifiers.control_key() => {}
+                                    _ => {
+                                        if let Some(text) = &event.text {
+                                            annotate.type_text(text);
+                                        }
+                                    }
+                                }
+                                win.window.request_redraw();
+                                if !win.modifiers.control_key() {
+                                    return;
+                                }
+                            }
+
+                            let action = match event.logical_key.as_ref() {
+                                keyboard::Key::Named(NamedKey::ContextMenu) => {
+                                    win.open_menu(0.0, 0.0);
+                                    None
+                                }
+                                keyboard::Key::Named(NamedKey::F10)
+                                    if win.modifiers.shift_key() =>
+                                {
+                                    win.open_menu(0.0, 0.0);
+                                    None
+                                }
+                                keyboard::Key::Named(NamedKey::F1)
+                                | keyboard::Key::Character("?") => Some(Action::Help),
+                                keyboard::Key::Named(NamedKey::Escape) if win.show_help => {
+                                    Some(Action::Help)
+                                }
+                                keyboard::Key::Named(
+                                    key @ (NamedKey::ArrowUp
+                                    | NamedKey::ArrowDown
+                                    | NamedKey::PageUp
+                                    | NamedKey::PageDown),
+                                ) if win.show_help => {
+                                    let page = help::page(win.help_height(title_bar));
+                                    win.scroll_help(
+                                        match key {
+                                            NamedKey::ArrowUp => -1,
+                                            NamedKey::ArrowDown => 1,
+                                            NamedKey::PageUp => -page,
+                                            _ => page,
+                                        },
+                                        title_bar,
+                                    );
+                                    None
+                                }
+                                keyboard::Key::Named(NamedKey::Escape)
+                                    if win.eyedropper.is_some() =>
+                                {
+                                    Some(Action::Eyedropper)
+                                }
+                                keyboard::Key::Named(NamedKey::Escape) if win.measure.is_some() => {
+                                    Some(Action::Measure)
+                                }
+                                keyboard::Key::Named(NamedKey::Escape)
+                                    if win.annotate.is_some() =>
+                                {
+                                    Some(Action::Annotate)
+                                }
+                                keyboard::Key::Named(NamedKey::Escape) if win.crop.is_some() => {
+                                    Some(Action::Crop)
+                                }
+                                keyboard::Key::Named(NamedKey::Enter) if win.crop.is_some() => {
+                                    Some(Action::ApplyCrop)
+                                }
+                                keyboard::Key::Named(NamedKey::Escape) => Some(Action::Close),
+                                keyboard::Key::Named(NamedKey::Tab) => {
+                                    proxy
+                                        .send_event(MyUserEvent::CycleFocus {
+                                            from: window_id,
+                                            backwards: win.modifiers.shift_key(),
+                                        })
+                                        .unwrap();
+                                    None
+                                }
+                                keyboard::Key::Named(NamedKey::Space) => Some(Action::Collapse),
+                                keyboard::Key::Named(NamedKey::Enter)
+                                    if matches!(win.clipboard, ClipboardContent::Timer(_)) =>
+                                {
+                                    Some(Action::TimerStartPause)
+                                }
+                                keyboard::Key::Named(NamedKey::Backspace | NamedKey::Delete)
+                                    if matches!(win.clipboard, ClipboardContent::Timer(_)) =>
+                                {
+                                    Some(Action::TimerReset)
+                                }
+                                keyboard::Key::Named(
+                                    key @ (NamedKey::PageUp | NamedKey::PageDown),
+                                ) if win.scrolls() => {
+                                    win.scroll_by(if key == NamedKey::PageUp { -10 } else { 10 });
+                                    None
+                                }
+                                keyboard::Key::Named(
+                                    key @ (NamedKey::ArrowLeft
+                                    | NamedKey::ArrowRight
+                                    | NamedKey::ArrowUp
+                                    | NamedKey::ArrowDown),
+                                ) => {
+                                    let (dx, dy) = match key {
+                                        NamedKey::ArrowLeft => (-KEYBOARD_STEP, 0),
+                                        NamedKey::ArrowRight => (KEYBOARD_STEP, 0),
+                                        NamedKey::ArrowUp => (0, -KEYBOARD_STEP),
+                                        _ => (0, KEYBOARD_STEP),
+                                    };
+                                    if win.modifiers.shift_key() {
+                                        win.resize_by(dx, dy);
+                                    } else {
+                                        win.move_by(dx, dy);
+                                    }
+                                    None
+                                }
+                                keyboard::Key::Character(key)
+                                    if win.annotate.is_some() && !win.modifiers.control_key() =>
+                                {
+                                    if let Some(annotate) = &mut win.annotate {
+                                        annotate.key(key);
+                                    }
+                                    win.window.request_redraw();
+                                    None
+                                }
+                                _ if !win.modifiers.control_key() => None,
+                                // Ctrl + Alt + C starts a countdown
+                                keyboard::Key::Character("c") if !win.modifiers.alt_key() => {
+                                    Some(Action::Copy)
+                                }
+                                keyboard::Key::Character("e") => Some(Action::Export),
+                                keyboard::Key::Character("d") => Some(Action::Duplicate),
+                                keyboard::Key::Character("-") => Some(Action::OpacityDown),
+                                keyboard::Key::Character("=" | "+") => Some(Action::OpacityUp),
+                                // Ctrl + Alt + T leaves click-through
+                                keyboard::Key::Character("t") if !win.modifiers.alt_key() => {
+                                    win.set_click_through(true);
+                                    None
+                                }
+                                keyboard::Key::Character("k") => Some(Action::ChangeColor),
+                                keyboard::Key::Character("p") => Some(Action::ChangeLevel),
+                                keyboard::Key::Character("l") => Some(Action::ToggleExpiry),
+                                keyboard::Key::Character("b") => Some(Action::NextView),
+                                keyboard::Key::Character("i")
+                                    if matches!(win.clipboard, ClipboardContent::Image(_)) =>
+                                {
+                                    Some(Action::Eyedropper)
+                                }
+                                keyboard::Key::Character("m")
+                                    if matches!(win.clipboard, ClipboardContent::Image(_)) =>
+                                {
+                                    Some(Action::Measure)
+                                }
+                                // Ctrl + Alt + A arranges the notes
+                                keyboard::Key::Character("a")
+                                    if matches!(win.clipboard, ClipboardContent::Image(_))
+                                        && !win.modifiers.alt_key() =>
+                                {
+                                    Some(Action::Annotate)
+                                }
+                                keyboard::Key::Character("z") => Some(Action::Undo),
+                                keyboard::Key::Character("x")
+                                    if matches!(win.clipboard, ClipboardContent::Image(_)) =>
+                                {
+                                    Some(Action::Crop)
+                                }
+                                keyboard::Key::Character("o")
+                                    if matches!(win.clipboard, ClipboardContent::Image(_)) =>
+                                {
+                                    Some(Action::Rotate)
+                                }
+                                // Ctrl + Alt + R is the global reminder list
+                                keyboard::Key::Character("r") if !win.modifiers.alt_key() => {
+                                    Some(Action::Remind(RemindTime::In(config.snooze)))
+                                }
+                                _ => None,
+                            };
+
+                            if let Some(action) = action {
+                                if !win.perform(action, &mut clipboard, &proxy, &config, &font) {
+                                    windows_local.remove_elem(|e| e.window_id == window_id);
+                                }
+                            }
+                        }
+                        WindowEvent::MouseInput {
+                            device_id: _,
+                            state,
+                            button,
+                        } => {
+                            if !state.is_pressed() {
+                                win.column_drag = None;
+                                if let Some(measure) = &mut win.measure {
+                                    measure.dragging = false;
+                                }
+                                if let Some(crop) = &mut win.crop {
+                                    crop.dragging = false;
+                                }
+                                if let Some(annotate) =
+                                    win.annotate.as_mut().filter(|a| a.dragging())
+                                {
+                                    if let Some(mark) = annotate.finish() {
+                                        win.annotations.push(mark);
+                                    }
+                                    win.window.request_redraw();
+                                }
+                                return;
+                            }
+
+                            let hit = win.hit_test(title_bar, &font);
+                            let action = match (button, hit) {
+                                (MouseButton::Left, Hit::Menu(Some(item))) => {
+                                    Some(win.menu_items()[item].0)
+                                }
+                                (_, Hit::Menu(None)) => {
+                                    win.close_menu();
+                                    None
+                                }
+                                (MouseButton::Right, _) => {
+                                    win.open_menu(win.mouse_pos.x as f32, win.mouse_pos.y as f32);
+                                    None
+                                }
+                                (MouseButton::Left, Hit::Button(button)) => Some(button.action()),
+                                (MouseButton::Left, Hit::Timer(TimerButton::StartPause)) => {
+                                    Some(Action::TimerStartPause)
+                                }
+                                (MouseButton::Left, Hit::Timer(TimerButton::Reset)) => {
+                                    Some(Action::TimerReset)
+                                }
+                                (MouseButton::Left, Hit::ChecklistItem(index)) => {
+                                    Some(Action::CheckItem(index))
+                                }
+                                (MouseButton::Left, Hit::TreeRow(index)) => {
+                                    Some(Action::TreeRow(index))
+                                }
+                                (MouseButton::Left, Hit::TableCell(row, column)) => {
+                                    Some(Action::CopyCell(row, column))
+                                }
+                                (MouseButton::Left, Hit::Pixel) => Some(Action::PickColor),
+                                (MouseButton::Left, Hit::Measure) => {
+                                    let point = win.image_point(title_bar);
+                                    let line = win.modifiers.shift_key();
+                                    if let (Some(point), Some((width, height)), Some(measure)) =
+                                        (point, win.image_size(), &mut win.measure)
+                                    {
+                                        measure.start(point, width, height, line);
+                                        win.window.request_redraw();
+                                    }
+                                    None
+                                }
+                                (MouseButton::Left, Hit::Crop) => {
+                                    let point = win.image_point(title_bar);
+                                    if let (Some(point), Some((width, height)), Some(crop)) =
+                                        (point, win.image_size(), &mut win.crop)
+                                    {
+                                        crop.start(point, width, height);
+                                        win.window.request_redraw();
+                                    }
+                                    None
+                                }
+                                (MouseButton::Left, Hit::Annotate) => {
+                                    // a click elsewhere finishes the label being typed
+                                    if let Some(mark) = win
+                                        .annotate
+                                        .as_mut()
+                                        .filter(|a| a.typing())
+                                        .and_then(Annotate::finish)
+                                    {
+                                        win.annotations.push(mark);
+                                    }
+                                    let point = win.image_point(title_bar);
+                                    let zoom = win.image_zoom(title_bar);
+                                    if let (Some(point), Some(zoom), Some(annotate)) =
+                                        (point, zoom, &mut win.annotate)
+                                    {
+                                        annotate.start(point, zoom);
+                                    }
+                                    win.window.request_redraw();
+                                    None
+                                }
+                                (MouseButton::Left, Hit::SwatchRow(index)) => {
+                                    Some(Action::CopyFormat(index))
+                                }
+                                (MouseButton::Left, Hit::TableEdge(column)) => {
+                                    let area = win.content_area(title_bar);
+                                    win.column_drag =
+                                        win.shown_table().zip(area).map(|(table, area)| {
+                                            (column, table.column_left(area, &font, column))
+                                        });
+                                    None
+                                }
+                                (MouseButton::Left, Hit::Resize(direction)) => {
+                                    win.window.drag_resize_window(direction).unwrap();
+                                    None
+                                }
+                                (MouseButton::Left, Hit::Drag) if win.double_click() => {
+                                    Some(Action::Collapse)
+                                }
+                                (MouseButton::Left, Hit::Drag) => {
+                                    win.window.drag_window().unwrap();
+                                    None
+                                }
+                                _ => None,
+                            };
+
+                            if let Some(action) = action {
+                                if !win.perform(action, &mut clipboard, &proxy, &config, &font) {
+                                    windows_local.remove_elem(|e| e.window_id == window_id);
+                                }
+                            }
+                        }
+                        WindowEvent::CursorMoved {
+                            device_id: _,
+                            position,
+                        } => {
+                            win.mouse_pos = position;
+
+                            if let Some(eyedropper) = &mut win.eyedropper {
+                                eyedropper.cursor = Some((position.x as f32, position.y as f32));
+                                win.window.request_redraw();
+                            }
+
+                            if win.measure.as_ref().is_some_and(|m| m.dragging) {
+                                let point = win.image_point(title_bar);
+                                if let (Some(point), Some((width, height)), Some(measure)) =
+                                    (point, win.image_size(), &mut win.measure)
+                                {
+                                    measure.drag_to(point, width, height);
+                                }
+                                win.window.request_redraw();
+                                return;
+                            }
+
+                            if win.crop.as_ref().is_some_and(|c| c.dragging) {
+                                let point = win.image_point(title_bar);
+                                if let (Some(point), Some((width, height)), Some(crop)) =
+                                    (point, win.image_size(), &mut win.crop)
+                                {
+                                    crop.drag_to(point, width, height);
+                                }
+                                win.window.request_redraw();
+                                return;
+                            }
+
+                            if win.annotate.as_ref().is_some_and(Annotate::dragging) {
+                                let point = win.image_point(title_bar);
+                                if let (Some(point), Some(annotate)) = (point, &mut win.annotate) {
+                                    annotate.drag_to(point);
+                                }
+                                win.window.request_redraw();
+                                return;
+                            }
+
+                            if let Some((column, left)) = win.column_drag {
+                                if let Some(table) = &mut win.table {
+                                    table.resize_column(column, position.x as f32 - left);
+                                }
+                                win.window.request_redraw();
+                                return;
+                            }
+
+                            let hit = win.hit_test(title_bar, &font);
+                            win.window.set_cursor_icon(match hit {
+                                Hit::Resize(direction) => CursorIcon::from(direction),
+                                Hit::TableEdge(_) => CursorIcon::ColResize,
+                                Hit::Pixel | Hit::Measure | Hit::Crop => CursorIcon::Crosshair,
+                                Hit::Annotate
+                                    if win
+                                        .annotate
+                                        .as_ref()
+                                        .is_some_and(|a| a.tool == annotate::Tool::Text) =>
+                                {
+                                    CursorIcon::Text
+                                }
+                                Hit::Annotate => CursorIcon::Crosshair,
+                                _ => CursorIcon::Pointer,
+                            });
+
+                            if let (Some(menu), Hit::Menu(Some(item))) = (&mut win.menu, &hit) {
+                                if menu.selected != *item {
+                                    menu.selected = *item;
+                                    win.window.request_redraw();
+                                }
+                            }
+
+                            let row = match hit {
+                                Hit::TreeRow(row) => Some(row),
+                                _ => None,
+                            };
+                            if let Some(tree) =
+                                win.tree.as_mut().filter(|_| win.view == TextView::Tree)
+                            {
+                                if tree.hover(row) {
+                                    win.window.request_redraw();
+                                }
+                            }
+
+                            let hovered = match hit {
+                                Hit::Button(button) => Some(button),
+                                _ => None,
+                            };
+                            if hovered != win.hovered {
+                                win.hovered = hovered;
+                                win.window.request_redraw();
+                            }
+                        }
+                        WindowEvent::CursorLeft { .. } => {
+                            if let Some(eyedropper) = &mut win.eyedropper {
+                                eyedropper.cursor = None;
+                            }
+                            win.hovered = None;
+                            win.window.request_redraw();
+                        }
+                        WindowEvent::RedrawRequested => {
+                            let (width, height) = {
+                                let size = win.window.inner_size();
+                                (size.width, size.height)
+                            };
+                            win.surface
+                                .resize(
+                                    NonZeroU32::new(width).unwrap(),
+                                    NonZeroU32::new(height).unwrap(),
+                                )
+                                .unwrap();
+
+                            let mut pixmap = Pixmap::new(width, height).unwrap();
+                            pixmap.fill(theme.note_background(win.color_tag));
+
+                            if let Some(tag_color) = win.color_tag.color() {
+                                fill_rect(
+                                    &mut pixmap,
+                                    Rect::from_xywh(0.0, 0.0, 5.0, height as f32).unwrap(),
+                                    tag_color,
+                                );
+                            }
+
+                            if win.flash_on(Instant::now()) {
+                                let (width, height) = (width as f32, height as f32);
+                                for border in [
+                                    Rect::from_xywh(0.0, 0.0, width, FLASH_BORDER),
+                                    Rect::from_xywh(
+                                        0.0,
+                                        height - FLASH_BORDER,
+                                        width,
+                                        FLASH_BORDER,
+                                    ),
+                                    Rect::from_xywh(0.0, 0.0, FLASH_BORDER, height),
+                                    Rect::from_xywh(
+                                        width - FLASH_BORDER,
+                                        0.0,
+                                        FLASH_BORDER,
+                                        height,
+                                    ),
+                                ]
+                                .into_iter()
+                                .flatten()
+                                {
+                                    fill_rect(&mut pixmap, border, theme.text);
+                                }
+                            }
+
+                            let note_title_bar = win.has_title_bar(title_bar);
+                            let top = if note_title_bar {
+                                TITLE_BAR_HEIGHT
+                            } else {
+                                0.0
+                            };
+                            if !win.collapsed {
+                                if let Some(area) = win.content_area(title_bar) {
+                                    let fonts = Fonts {
+                                        regular: &font,
+                                        mono: &mono,
+                                    };
+                                    win.draw_content(
+                                        &mut pixmap,
+                                        area,
+                                        &fonts,
+                                        &theme,
+                                        config.font_size,
+                                    );
+                                }
+                            }
+
+                            let title = win.title();
+                            let state = TitleBarState {
+                                title: &title,
+                                hovered: win.hovered,
+                                level: win.level,
+                                collapsed: win.collapsed,
+                                title_bar: note_title_bar,
+                            };
+                            title_bar::draw(&mut pixmap, &font, &theme, &state);
+                            title_bar::draw_tooltip(&mut pixmap, &font, &theme, &state);
+
+                            if let (Some(expiry), false) = (win.expiry, win.collapsed) {
+                                let left = expiry.fraction_left(Instant::now());
+                                let bar = Rect::from_xywh(
+                                    0.0,
+                                    height as f32 - EXPIRY_BAR_HEIGHT,
+                                    width as f32 * left,
+                                    EXPIRY_BAR_HEIGHT,
+                                );
+                                if let Some(bar) = bar {
+                                    fill_rect(
+                                        &mut pixmap,
+                                        bar,
+                                        win.color_tag.color().unwrap_or(theme.text),
+                                    );
+                                }
+                            }
+
+                            if win.show_help && !win.collapsed {
+                                help::draw(&mut pixmap, &font, &theme, top, win.help_scroll);
+                            }
+
+                            if let Some(menu) = &win.menu {
+                                menu.draw(&mut pixmap, &font, &theme, &win.menu_items());
+                            }
+
+                            // pixmap data is premultiplied, so fading the whole pixel
+                            // keeps it valid for the compositor
+                            let opacity =
+                                win.opacity * win.expiry.map_or(1.0, |e| e.fade(Instant::now()));
+                            let fade = |channel: u8| (channel as f32 * opacity) as u32;
+
+                            let mut buffer = win.surface.buffer_mut().unwrap();
+                            for (index, pixel) in pixmap.data().chunks_exact(4).enumerate() {
+                                buffer[index] = fade(pixel[2])
+                                    | fade(pixel[1]) << 8
+                                    | fade(pixel[0]) << 16
+                                    | fade(pixel[3]) << 24;
+                            }
+
+                            buffer.present().unwrap();
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        })
+        .unwrap();
+}
+
+#[derive(Debug)]
+enum MyUserEvent {
+    Popup {
+        window_builder: Box<WindowBuilder>,
+        clipboard: ClipboardContent,
+        /// Mouse position on the screen when the note was requested
+        cursor: Option<PhysicalPosition<f64>>,
+        /// The note closes by itself after this time
+        ttl: Option<Duration>,
+        /// Keep the note hidden until the reminder is due
+        reminder: Option<Reminder>,
+    },
+    LeaveClickThrough,
+    /// Tile all notes on the monitor, or on the primary one when it's not known
+    ArrangeAll(Option<MonitorHandle>),
+    /// Focus the note after the given one, or before it
+    CycleFocus {
+        from: WindowId,
+        backwards: bool,
+    },
+    /// Show a note with the pending reminders
+    ListReminders,
+}
+
+fn popup_clipboard(
+    content: ClipboardContent,
+    cursor: Option<PhysicalPosition<f64>>,
+    ttl: Option<Duration>,
+    reminder: Option<Reminder>,
+) -> MyUserEvent {
+    MyUserEvent::Popup {
+        window_builder: Box::new(
+            WindowBuilder::new()
+                .with_enabled_buttons(WindowButtons::empty())
+                .with_decorations(false)
+                .with_transparent(true)
+                .with_window_level(WindowLevel::AlwaysOnTop)
+                .with_title("FONotes - ".to_owned() + content.kind())
+                .with_resizable(true)
+                // shown once it's placed
+                .with_visible(false)
+                .with_min_inner_size(MIN_NOTE_SIZE),
+        ),
+        clipboard: content,
+        cursor,
+        ttl,
+        reminder,
+    }
+}
+
+/// Text listing reminders with their times, soonest first
+fn reminder_list(reminders: impl Iterator<Item = (DateTime<Local>, String)>) -> String {
+    let mut reminders: Vec<_> = reminders.collect();
+    if reminders.is_empty() {
+        return "No reminders".to_string();
+    }
+
+    reminders.sort_by_key(|(due, _)| *due);
+    reminders
+        .iter()
+        .map(|(due, preview)| format!("{} · {}", due.format("%a %H:%M"), preview))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn main() {
+    let args = Args::parse();
+    let mut config = Config::load();
+    if args.language.is_some() {
+        config.language = args.language;
+    }
+
+    let saved_reminders = reminders::load();
+    if args.reminders {
+        println!(
+            "{}",
+            reminder_list(saved_reminders.iter().map(|(reminder, content)| {
+                let preview = match content {
+                    ClipboardContent::Text(t) => t.lines().next().unwrap_or_default().to_string(),
+                    ClipboardContent::Image(i) => format!("Image {}x{}", i.width, i.height),
+                    ClipboardContent::Timer(_) | ClipboardContent::None => String::new(),
+                };
+                (reminder.due, preview)
+            }))
+        );
+        return;
+    }
+
+    let event_loop = create_event_loop();
+    let windows = RefCell::new(Vec::new());
+
+    let event_loop_proxy = event_loop.create_proxy();
+
+    for (reminder, content) in saved_reminders {
+        event_loop_proxy
+            .send_event(popup_clipboard(content, None, None, Some(reminder)))
+            .unwrap();
+    }
+
+    if args.stdin {
+        let mut text = String::new();
+        match std::io::stdin().read_to_string(&mut text) {
+            Ok(_) if !text.trim().is_empty() => {
+                event_loop_proxy
+                    .send_event(popup_clipboard(
+                        ClipboardContent::Text(text),
+                        None,
+                        args.ttl,
+                        None,
+                    ))
+                    .unwrap();
+            }
+            Ok(_) => eprintln!("stdin: nothing was piped in"),
+            Err(e) => eprintln!("stdin: {e}"),
+        }
+    }
+
+    let countdown = config.countdown;
+
+    // --ttl makes every note temporary, otherwise only the ones made with shift held
+    let ttl = args.ttl;
+    let shift_ttl = args.ttl.unwrap_or(config.ttl);
+
+    thread::spawn(move || {
+        let mut pressed: Vec<Key> = Vec::new();
+        let mut cursor: Option<PhysicalPosition<f64>> = None;
+        let mut clipboard = Clipboard::new().unwrap();
+
+        listen(move |event: rdev::Event| {
+            if let EventType::MouseMove { x, y } = event.event_type {
+                cursor = Some(PhysicalPosition::new(x, y));
+            } else if let EventType::KeyPress(key) = event.event_type {
+                if !pressed.contains(&key) {
+                    pressed.push(key);
+                }
+
+                if key == Key::KeyN
+                    && pressed.contains(&Key::ControlLeft)
+                    && pressed.contains(&Key::Alt)
+                {
+                    let ttl = if pressed.contains(&Key::ShiftLeft)
+                        || pressed.contains(&Key::ShiftRight)
+                    {
+                        Some(shift_ttl)
+                    } else {
+                        ttl
+                    };
+
+                    event_loop_proxy
+                        .send_event(popup_clipboard(
+                            get_clipboard(&mut clipboard),
+                            cursor,
+                            ttl,
+                            None,
+                        ))
+                        .unwrap();
+                } else if key == Key::KeyC
+                    && pressed.contains(&Key::ControlLeft)
+                    && pressed.contains(&Key::Alt)
+                {
+                    // a length copied to the clipboard beats the configured one
+                    let duration = clipboard
+                        .get_text()
+                        .ok()
+                        .and_then(|text| timer::parse_duration(&text))
+                        .unwrap_or(countdown);
+                    let timer = Timer::new(TimerKind::Countdown(duration));
+
+                    event_loop_proxy
+                        .send_event(popup_clipboard(
+                            ClipboardContent::Timer(timer),
+                            cursor,
+                            None,
+                            None,
+                        ))
+                        .unwrap();
+                } else if key == Key::KeyW
+                    && pressed.contains(&Key::ControlLeft)
+                    && pressed.contains(&Key::Alt)
+                {
+                    let timer = Timer::new(TimerKind::Stopwatch);
+
+                    event_loop_proxy
+                        .send_event(popup_clipboard(
+                            ClipboardContent::Timer(timer),
+                            cursor,
+                            None,
+                            None,
+                        ))
+                        .unwrap();
+                } else if key == Key::KeyA
+                    && pressed.contains(&Key::ControlLeft)
+                    && pressed.contains(&Key::Alt)
+                {
+                    event_loop_proxy
+                        .send_event(MyUserEvent::ArrangeAll(None))
+                        .unwrap();
+                } else if key == Key::KeyR
+                    && pressed.contains(&Key::ControlLeft)
+                    && pressed.contains(&Key::Alt)
+                {
+                    event_loop_proxy
+                        .send_event(MyUserEvent::ListReminders)
+                        .unwrap();
+                } else if key == Key::KeyT
                     && pressed.contains(&Key::ControlLeft)
                     && pressed.contains(&Key::Alt)
                 {
                     event_loop_proxy
-                        .send_event(popup_clipboard(get_clipboard(&mut clipboard)))
+                        .send_event(MyUserEvent::LeaveClickThrough)
                         .unwrap();
                 }
             } else if let EventType::KeyRelease(key) = event.event_type {
@@ -622,5 +2431,5 @@ fn main() {
         .unwrap();
     });
 
-    run_event_loop(event_loop, windows);
+    run_event_loop(event_loop, windows, config);
 }
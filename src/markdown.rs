@@ -0,0 +1,253 @@
+use tiny_skia::Color;
+
+use crate::render::expand_tabs;
+use crate::rich::{Line, Span, Style};
+use crate::theme::Theme;
+
+const HEADING_SCALES: [f32; 6] = [1.8, 1.5, 1.3, 1.15, 1.0, 1.0];
+
+/// Turns markdown into styled lines, block by block
+pub fn parse(text: &str, theme: &Theme) -> Vec<Line> {
+    let code_background = faded(theme.text, 0.1);
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            let code = Style {
+                mono: true,
+                ..Style::default()
+            };
+            lines.push(Line {
+                spans: vec![Span::new(expand_tabs(raw, 4), code)],
+                background: Some(code_background),
+                wrap: false,
+                ..Line::default()
+            });
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            lines.push(Line {
+                scale: 0.5,
+                ..Line::default()
+            });
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let bold = Style {
+                bold: true,
+                ..Style::default()
+            };
+            lines.push(Line {
+                spans: inline(trimmed[hashes..].trim(), bold, theme),
+                scale: HEADING_SCALES[hashes - 1],
+                ..Line::default()
+            });
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            lines.push(Line {
+                rule: true,
+                ..Line::default()
+            });
+            continue;
+        }
+
+        if trimmed.starts_with('>') {
+            let depth = trimmed
+                .chars()
+                .take_while(|c| *c == '>' || *c == ' ')
+                .filter(|c| *c == '>')
+                .count();
+            let quoted = trimmed.trim_start_matches(['>', ' ']);
+            let style = Style {
+                color: Some(faded(theme.text, 0.7)),
+                ..Style::default()
+            };
+            lines.push(Line {
+                spans: inline(quoted, style, theme),
+                indent: depth as f32 * 0.8,
+                bar: Some(theme.accent),
+                ..Line::default()
+            });
+            continue;
+        }
+
+        // two spaces of indent are one level of a nested list
+        let depth = (raw.len() - trimmed.len()) / 2;
+        if let Some((marker, item)) = list_item(trimmed) {
+            // a digit is about half as wide as it's high
+            let hanging = marker.chars().count() as f32 * 0.5 + 0.3;
+            let mut spans = vec![Span::new(marker + " ", Style::default())];
+            spans.extend(inline(item, Style::default(), theme));
+            lines.push(Line {
+                spans,
+                indent: depth as f32 * 1.2 + 0.4,
+                hanging,
+                ..Line::default()
+            });
+            continue;
+        }
+
+        lines.push(Line::new(inline(trimmed, Style::default(), theme)));
+    }
+
+    lines
+}
+
+fn faded(color: Color, opacity: f32) -> Color {
+    let mut color = color;
+    color.apply_opacity(opacity);
+    color
+}
+
+/// `---`, `***` or `___`, spaces allowed in between
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|mark| marks.iter().all(|c| c == mark))
+}
+
+/// Bullet or number of a list item and the rest of the line
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), item));
+        }
+    }
+
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((line[..digits + 1].to_string(), &rest[2..]));
+    }
+
+    None
+}
+
+/// Spans of the inline markup: emphasis, code, strikethrough and links
+fn inline(text: &str, base: Style, theme: &Theme) -> Vec<Span> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut style = base;
+    let mut current = String::new();
+    let mut i = 0;
+
+    let flush = |spans: &mut Vec<Span>, current: &mut String, style: Style| {
+        if !current.is_empty() {
+            spans.push(Span::new(std::mem::take(current), style));
+        }
+    };
+    let find = |from: usize, pattern: &[char]| {
+        (from..chars.len()).find(|j| chars[*j..].starts_with(pattern))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        match c {
+            '\\' if next.is_some_and(|n| n.is_ascii_punctuation()) => {
+                current.push(next.unwrap());
+                i += 2;
+                continue;
+            }
+            '`' => {
+                if let Some(end) = find(i + 1, &['`']) {
+                    flush(&mut spans, &mut current, style);
+                    let code: String = chars[i + 1..end].iter().collect();
+                    spans.push(Span::new(
+                        code,
+                        Style {
+                            mono: true,
+                            background: Some(faded(theme.text, 0.1)),
+                            ..style
+                        },
+                    ));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '*' | '_' if next == Some(c) => {
+                flush(&mut spans, &mut current, style);
+                style.bold = !style.bold;
+                i += 2;
+                continue;
+            }
+            '*' | '_' => {
+                let before = i.checked_sub(1).map(|j| chars[j]);
+                // `snake_case` and `2 * 3` are not emphasis
+                let word_inside = c == '_'
+                    && before.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric);
+                let loose = !style.italic && next.is_none_or(char::is_whitespace);
+                if !word_inside && !loose {
+                    flush(&mut spans, &mut current, style);
+                    style.italic = !style.italic;
+                    i += 1;
+                    continue;
+                }
+            }
+            '~' if next == Some('~') => {
+                flush(&mut spans, &mut current, style);
+                style.strike = !style.strike;
+                i += 2;
+                continue;
+            }
+            '[' => {
+                let link = find(i + 1, &[']', '('])
+                    .and_then(|middle| Some((middle, find(middle + 2, &[')'])?)));
+                if let Some((middle, end)) = link {
+                    flush(&mut spans, &mut current, style);
+                    let label: String = chars[i + 1..middle].iter().collect();
+                    let link_style = Style {
+                        color: Some(theme.accent),
+                        underline: true,
+                        ..style
+                    };
+                    spans.extend(inline(&label, link_style, theme));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '<' => {
+                if let Some(end) = find(i + 1, &['>']) {
+                    let url: String = chars[i + 1..end].iter().collect();
+                    if url.starts_with("http://") || url.starts_with("https://") {
+                        flush(&mut spans, &mut current, style);
+                        spans.push(Span::new(
+                            url,
+                            Style {
+                                color: Some(theme.accent),
+                                underline: true,
+                                ..style
+                            },
+                        ));
+                        i = end + 1;
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        current.push(c);
+        i += 1;
+    }
+    flush(&mut spans, &mut current, style);
+
+    spans
+}
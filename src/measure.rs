@@ -0,0 +1,141 @@
+use fontdue::Font;
+use tiny_skia::{Color, LineCap, Paint, PathBuilder, Pixmap, Point, Rect, Stroke, Transform};
+
+use crate::render::{draw_glyphs, fill_rect, image_transform, text_width};
+use crate::theme::Theme;
+
+/// Zoom the pixel grid shows up from
+const GRID_ZOOM: f32 = 4.0;
+const FONT_SIZE: f32 = 12.0;
+const LABEL_HEIGHT: f32 = 20.0;
+
+/// Measuring distances on an image note, in image pixels
+#[derive(Debug, Clone, Default)]
+pub struct Measure {
+    /// Where the drag started and where it is now, on pixel edges
+    pub span: Option<(Point, Point)>,
+    pub dragging: bool,
+    /// A line is measured instead of a rectangle
+    line: bool,
+}
+
+/// The point on the nearest pixel edge, so measurements are whole pixels
+pub fn snap(point: Point, width: u32, height: u32) -> Point {
+    Point::from_xy(
+        point.x.round().clamp(0.0, width as f32),
+        point.y.round().clamp(0.0, height as f32),
+    )
+}
+
+impl Measure {
+    pub fn start(&mut self, point: Point, width: u32, height: u32, line: bool) {
+        let point = snap(point, width, height);
+        self.span = Some((point, point));
+        self.dragging = true;
+        self.line = line;
+    }
+
+    pub fn drag_to(&mut self, point: Point, width: u32, height: u32) {
+        if let Some((_, end)) = &mut self.span {
+            *end = snap(point, width, height);
+        }
+    }
+
+    /// Width, height and length of the diagonal
+    fn size(&self) -> Option<(f32, f32, f32)> {
+        let (start, end) = self.span?;
+        let (w, h) = ((end.x - start.x).abs(), (end.y - start.y).abs());
+        Some((w, h, w.hypot(h)))
+    }
+
+    pub fn draw(
+        &self,
+        pixmap: &mut Pixmap,
+        area: Rect,
+        (width, height): (u32, u32),
+        font: &Font,
+        theme: &Theme,
+    ) {
+        let transform = image_transform(area, width, height);
+        let zoom = transform.sx.min(transform.sy);
+        if zoom >= GRID_ZOOM {
+            draw_grid(pixmap, area, (width, height), transform);
+        }
+
+        let (Some((start, end)), Some((w, h, distance))) = (self.span, self.size()) else {
+            return;
+        };
+        let mut points = [start, end];
+        transform.map_points(&mut points);
+        let [start, end] = points;
+
+        let mut paint = Paint::default();
+        paint.set_color(theme.text);
+        paint.anti_alias = true;
+        let stroke = Stroke {
+            width: 1.5,
+            line_cap: LineCap::Square,
+            ..Stroke::default()
+        };
+
+        let path = if self.line {
+            let mut path = PathBuilder::new();
+            path.move_to(start.x, start.y);
+            path.line_to(end.x, end.y);
+            path.finish()
+        } else {
+            Rect::from_ltrb(
+                start.x.min(end.x),
+                start.y.min(end.y),
+                start.x.max(end.x),
+                start.y.max(end.y),
+            )
+            .map(PathBuilder::from_rect)
+        };
+        if let Some(path) = path {
+            pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+        }
+
+        let label = format!("{w} × {h} px · {:.1} px", distance);
+        let label_width = text_width(&label, FONT_SIZE, font) + 8.0;
+        // under the end of the drag, kept inside the note
+        let x = (end.x + 8.0)
+            .min(area.right() - label_width)
+            .max(area.left());
+        let y = (end.y + 8.0)
+            .min(area.bottom() - LABEL_HEIGHT)
+            .max(area.top());
+        if let Some(rect) = Rect::from_xywh(x, y, label_width, LABEL_HEIGHT) {
+            fill_rect(pixmap, rect, theme.background);
+        }
+        draw_glyphs(
+            pixmap,
+            &label,
+            x + 4.0,
+            y + 14.0,
+            FONT_SIZE,
+            font,
+            theme.text,
+            false,
+            false,
+        );
+    }
+}
+
+/// Lines between the image pixels, once they are big enough to tell apart
+fn draw_grid(pixmap: &mut Pixmap, area: Rect, (width, height): (u32, u32), transform: Transform) {
+    let grid = Color::from_rgba8(128, 128, 128, 90);
+
+    for column in 1..width {
+        let x = transform.sx * column as f32 + transform.tx;
+        if let Some(line) = Rect::from_xywh(x, area.top(), 1.0, area.height()) {
+            fill_rect(pixmap, line, grid);
+        }
+    }
+    for row in 1..height {
+        let y = transform.sy * row as f32 + transform.ty;
+        if let Some(line) = Rect::from_xywh(area.left(), y, area.width(), 1.0) {
+            fill_rect(pixmap, line, grid);
+        }
+    }
+}
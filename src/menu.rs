@@ -0,0 +1,405 @@
+use std::time::Duration;
+
+use fontdue::Font;
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Transform};
+use winit::window::WindowLevel;
+
+use crate::annotate::Tool;
+use crate::code::Language;
+use crate::expiry::format_duration;
+use crate::reminders::RemindTime;
+use crate::render::{fill_rect, render_text};
+use crate::theme::{ColorTag, Theme};
+use crate::view::TextView;
+
+const MENU_WIDTH: f32 = 170.0;
+const ITEM_HEIGHT: f32 = 22.0;
+const FONT_SIZE: f32 = 13.0;
+
+/// Something that can be done with a note from the menu, title bar or keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Copy,
+    Export,
+    ChangeColor,
+    ChangeLevel,
+    OpacityUp,
+    OpacityDown,
+    Duplicate,
+    Collapse,
+    ArrangeAll,
+    Help,
+    ToggleExpiry,
+    RemindMenu,
+    Remind(RemindTime),
+    MenuBack,
+    TimerStartPause,
+    TimerReset,
+    NextView,
+    NextLanguage,
+    CheckItem(usize),
+    TreeRow(usize),
+    CopyCell(usize, usize),
+    StripEscapes,
+    CopyFormat(usize),
+    Eyedropper,
+    PickColor,
+    Measure,
+    Annotate,
+    NextTool,
+    NextMarkColor,
+    Undo,
+    Crop,
+    ApplyCrop,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+    RevertImage,
+    Close,
+}
+
+impl Action {
+    /// Actions that tweak the note and leave the menu open to tweak it further
+    pub fn keeps_menu(self) -> bool {
+        matches!(
+            self,
+            Action::ChangeColor
+                | Action::OpacityUp
+                | Action::OpacityDown
+                | Action::RemindMenu
+                | Action::MenuBack
+                | Action::NextView
+                | Action::NextLanguage
+                | Action::NextTool
+                | Action::NextMarkColor
+                | Action::Rotate
+                | Action::FlipHorizontal
+                | Action::FlipVertical
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuPage {
+    Main,
+    Remind,
+}
+
+/// Note properties shown in the menu labels
+pub struct MenuState {
+    pub level: WindowLevel,
+    pub color_tag: ColorTag,
+    pub opacity: f32,
+    pub collapsed: bool,
+    pub expires_in: Option<Duration>,
+    /// Whether the timer of a timer note is running
+    pub timer_running: Option<bool>,
+    /// How a text note is shown
+    pub view: Option<TextView>,
+    /// Language of a note shown as code
+    pub language: Option<Language>,
+    /// Whether the text has terminal escape sequences in it
+    pub escapes: bool,
+    /// Whether colors are picked from an image note, `None` for other notes
+    pub eyedropper: Option<bool>,
+    /// Whether an image note is being measured, `None` for other notes
+    pub measuring: Option<bool>,
+    /// Whether an image note is being drawn over, `None` for other notes
+    pub annotating: Option<bool>,
+    /// Tool and color marks are drawn with while annotating
+    pub annotate: Option<(Tool, &'static str)>,
+    /// Marks drawn over an image note
+    pub marks: usize,
+    /// Whether the part of an image note to keep is selected, `None` for other notes
+    pub cropping: Option<bool>,
+    /// Whether there is a selection to crop to
+    pub selection: bool,
+    /// Whether an image note was cropped, rotated or flipped
+    pub edited: bool,
+}
+
+pub fn items(state: &MenuState, page: MenuPage) -> Vec<(Action, String)> {
+    if page == MenuPage::Remind {
+        let mut items = vec![(Action::MenuBack, "< Back".to_string())];
+        items.extend(
+            RemindTime::CHOICES
+                .iter()
+                .map(|time| (Action::Remind(*time), time.label())),
+        );
+        return items;
+    }
+
+    let mut items = Vec::new();
+    if let Some(running) = state.timer_running {
+        let label = if running { "Pause" } else { "Start" };
+        items.push((Action::TimerStartPause, label.to_string()));
+        items.push((Action::TimerReset, "Reset".to_string()));
+    }
+
+    items.extend([
+        (Action::Copy, "Copy".to_string()),
+        (Action::Export, "Export to file".to_string()),
+        (
+            Action::ChangeColor,
+            format!("Color: {}", state.color_tag.name()),
+        ),
+        (
+            Action::ChangeLevel,
+            format!("Level: {}", level_name(state.level)),
+        ),
+        (
+            Action::OpacityUp,
+            format!("Opacity + ({}%)", (state.opacity * 100.0).round()),
+        ),
+        (Action::OpacityDown, "Opacity -".to_string()),
+        (
+            Action::Collapse,
+            if state.collapsed {
+                "Expand".to_string()
+            } else {
+                "Collapse".to_string()
+            },
+        ),
+        (
+            Action::ToggleExpiry,
+            match state.expires_in {
+                Some(left) => format!("Keep (closes in {})", format_duration(left)),
+                None => "Close by itself".to_string(),
+            },
+        ),
+    ]);
+    // timers can't be snoozed
+    if state.timer_running.is_none() {
+        items.push((Action::RemindMenu, "Remind me >".to_string()));
+    }
+    if let Some(view) = state.view {
+        items.push((Action::NextView, format!("View: {}", view.name())));
+    }
+    if let Some(language) = state.language {
+        items.push((
+            Action::NextLanguage,
+            format!("Language: {}", language.name()),
+        ));
+    }
+    if state.escapes {
+        items.push((Action::StripEscapes, "Strip escape codes".to_string()));
+    }
+    if let Some(picking) = state.eyedropper {
+        let label = if picking {
+            "Stop picking colors"
+        } else {
+            "Pick a color"
+        };
+        items.push((Action::Eyedropper, label.to_string()));
+    }
+    if let Some(measuring) = state.measuring {
+        let label = if measuring {
+            "Stop measuring"
+        } else {
+            "Measure"
+        };
+        items.push((Action::Measure, label.to_string()));
+    }
+    if let Some(annotating) = state.annotating {
+        let label = if annotating {
+            "Stop annotating"
+        } else {
+            "Annotate"
+        };
+        items.push((Action::Annotate, label.to_string()));
+    }
+    if let Some((tool, color)) = state.annotate {
+        items.push((Action::NextTool, format!("Tool: {}", tool.name())));
+        items.push((Action::NextMarkColor, format!("Mark color: {color}")));
+    }
+    if state.marks > 0 {
+        items.push((Action::Undo, "Undo mark".to_string()));
+    }
+    if let Some(cropping) = state.cropping {
+        let label = if cropping { "Stop cropping" } else { "Crop" };
+        items.push((Action::Crop, label.to_string()));
+        if state.selection {
+            items.push((Action::ApplyCrop, "Crop to selection".to_string()));
+        }
+        items.extend([
+            (Action::Rotate, "Rotate 90°".to_string()),
+            (Action::FlipHorizontal, "Flip horizontally".to_string()),
+            (Action::FlipVertical, "Flip vertically".to_string()),
+        ]);
+    }
+    if state.edited {
+        items.push((Action::RevertImage, "Revert to original".to_string()));
+    }
+    items.extend([
+        (Action::Duplicate, "Duplicate".to_string()),
+        (Action::ArrangeAll, "Arrange all notes".to_string()),
+        (Action::Help, "Keyboard shortcuts".to_string()),
+        (Action::Close, "Close".to_string()),
+    ]);
+    items
+}
+
+pub fn level_name(level: WindowLevel) -> &'static str {
+    match level {
+        WindowLevel::AlwaysOnTop => "On top",
+        WindowLevel::Normal => "Normal",
+        WindowLevel::AlwaysOnBottom => "Desktop",
+    }
+}
+
+pub struct ContextMenu {
+    pub x: f32,
+    pub y: f32,
+    pub selected: usize,
+    pub page: MenuPage,
+    /// First item shown when the note is too small for the whole menu
+    first: usize,
+}
+
+/// Items that fit into a note of given height, at least one
+fn rows(count: usize, height: f32) -> usize {
+    count.min(((height / ITEM_HEIGHT) as usize).max(1))
+}
+
+impl ContextMenu {
+    pub fn new(x: f32, y: f32) -> Self {
+        ContextMenu {
+            x,
+            y,
+            selected: 0,
+            page: MenuPage::Main,
+            first: 0,
+        }
+    }
+
+    pub fn open_page(&mut self, page: MenuPage) {
+        self.page = page;
+        self.selected = 0;
+        self.first = 0;
+    }
+
+    /// Keeps the menu inside the note, it scrolls when the note is too small for it
+    fn rect(&self, count: usize, width: f32, height: f32) -> Rect {
+        let menu_height = ITEM_HEIGHT * rows(count, height) as f32;
+        let x = self.x.min(width - MENU_WIDTH).max(0.0);
+        let y = self.y.min(height - menu_height).max(0.0);
+        Rect::from_xywh(x, y, MENU_WIDTH, menu_height).unwrap()
+    }
+
+    pub fn item_at(&self, count: usize, width: f32, height: f32, x: f32, y: f32) -> Option<usize> {
+        let rect = self.rect(count, width, height);
+        if x < rect.left() || x >= rect.right() || y < rect.top() || y >= rect.bottom() {
+            return None;
+        }
+        let item = self.first(count, height) + ((y - rect.top()) / ITEM_HEIGHT) as usize;
+        (item < count).then_some(item)
+    }
+
+    pub fn select_next(&mut self, count: usize, height: f32) {
+        self.selected = (self.selected + 1) % count;
+        self.follow(count, height);
+    }
+
+    pub fn select_previous(&mut self, count: usize, height: f32) {
+        self.selected = (self.selected + count - 1) % count;
+        self.follow(count, height);
+    }
+
+    /// Moves the selection by the wheel, without wrapping around
+    pub fn scroll_by(&mut self, lines: i32, count: usize, height: f32) {
+        let last = count.saturating_sub(1) as i32;
+        self.selected = (self.selected as i32 + lines).clamp(0, last) as usize;
+        self.follow(count, height);
+    }
+
+    /// Keeps the selection on an item, the items change with the note
+    pub fn clamp(&mut self, count: usize, height: f32) {
+        self.selected = self.selected.min(count.saturating_sub(1));
+        self.follow(count, height);
+    }
+
+    /// Scrolls the menu so the selected item is shown
+    fn follow(&mut self, count: usize, height: f32) {
+        let rows = rows(count, height);
+        if self.selected < self.first {
+            self.first = self.selected;
+        } else if self.selected >= self.first + rows {
+            self.first = self.selected + 1 - rows;
+        }
+        self.first = self.first(count, height);
+    }
+
+    /// First item shown, the note may have grown since the menu was scrolled
+    fn first(&self, count: usize, height: f32) -> usize {
+        self.first.min(count - rows(count, height))
+    }
+
+    pub fn draw(
+        &self,
+        pixmap: &mut Pixmap,
+        font: &Font,
+        theme: &Theme,
+        items: &[(Action, String)],
+    ) {
+        let rect = self.rect(items.len(), pixmap.width() as f32, pixmap.height() as f32);
+
+        let border = rect.outset(1.0, 1.0).unwrap();
+        fill_rect(pixmap, border, theme.text);
+        fill_rect(pixmap, rect, theme.background);
+
+        let height = pixmap.height() as f32;
+        let (rows, first) = (rows(items.len(), height), self.first(items.len(), height));
+        let shown = items.iter().enumerate().skip(first).take(rows);
+        for (row, (i, (_, label))) in shown.enumerate() {
+            let item = Rect::from_xywh(
+                rect.x(),
+                rect.y() + ITEM_HEIGHT * row as f32,
+                rect.width(),
+                ITEM_HEIGHT,
+            )
+            .unwrap();
+
+            let color = if i == self.selected {
+                fill_rect(pixmap, item, theme.text);
+                theme.background
+            } else {
+                theme.text
+            };
+
+            let text = render_text(label.clone(), FONT_SIZE, font, color);
+            pixmap.draw_pixmap(
+                item.x() as i32 + 8,
+                (item.y() + (ITEM_HEIGHT - text.height() as f32) / 2.0) as i32,
+                text.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+
+            // arrows on the edge rows show there are more items to scroll to
+            let more_above = row == 0 && first > 0;
+            let more_below = row + 1 == rows && first + rows < items.len();
+            if more_above || more_below {
+                let (x, y) = (item.right() - 12.0, item.y() + ITEM_HEIGHT / 2.0);
+                let tip = if more_above { -3.0 } else { 3.0 };
+                let mut path = PathBuilder::new();
+                path.move_to(x - 4.0, y - tip);
+                path.line_to(x + 4.0, y - tip);
+                path.line_to(x, y + tip);
+                path.close();
+                let mut paint = Paint::default();
+                paint.set_color(color);
+                paint.anti_alias = true;
+                if let Some(path) = path.finish() {
+                    pixmap.fill_path(
+                        &path,
+                        &paint,
+                        FillRule::Winding,
+                        Transform::identity(),
+                        None,
+                    );
+                }
+            }
+        }
+    }
+}
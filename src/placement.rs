@@ -0,0 +1,138 @@
+use crate::snap::Bounds;
+
+const CASCADE_STEP: i32 = 30;
+const CORNER_MARGIN: i32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Where new notes show up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Under the mouse cursor, with the cursor on the title bar
+    Cursor,
+    /// A step down and right from the last note
+    Cascade,
+    Corner(Corner),
+    /// Where the last note with the same kind of content was moved to
+    Remember,
+}
+
+impl Placement {
+    pub fn by_name(name: &str) -> Option<Placement> {
+        match name {
+            "cursor" => Some(Placement::Cursor),
+            "cascade" => Some(Placement::Cascade),
+            "top-left" => Some(Placement::Corner(Corner::TopLeft)),
+            "top-right" => Some(Placement::Corner(Corner::TopRight)),
+            "bottom-left" => Some(Placement::Corner(Corner::BottomLeft)),
+            "bottom-right" => Some(Placement::Corner(Corner::BottomRight)),
+            "remember" => Some(Placement::Remember),
+            _ => None,
+        }
+    }
+}
+
+pub struct PlacementContext<'a> {
+    pub cursor: Option<(i32, i32)>,
+    pub last_note: Option<Bounds>,
+    pub remembered: Option<(i32, i32)>,
+    pub monitors: &'a [Bounds],
+}
+
+impl PlacementContext<'_> {
+    /// Monitor with the mouse cursor on it, the first one if that's not known
+    pub fn active_monitor(&self) -> Option<Bounds> {
+        self.cursor
+            .and_then(|(x, y)| self.monitors.iter().find(|m| m.contains(x, y)))
+            .or_else(|| self.monitors.first())
+            .copied()
+    }
+}
+
+/// Position for a new note of the given size, already clamped onto a monitor
+pub fn place(
+    placement: Placement,
+    width: i32,
+    height: i32,
+    context: &PlacementContext,
+) -> (i32, i32) {
+    let cascade = || match (context.last_note, context.active_monitor()) {
+        (Some(last), _) => (last.x + CASCADE_STEP, last.y + CASCADE_STEP),
+        (None, Some(monitor)) => (monitor.x + CASCADE_STEP, monitor.y + CASCADE_STEP),
+        (None, None) => (0, 0),
+    };
+
+    let (x, y) = match placement {
+        Placement::Cursor => match context.cursor {
+            Some((x, y)) => (x - width / 2, y - CORNER_MARGIN / 2),
+            None => cascade(),
+        },
+        Placement::Cascade => {
+            let (x, y) = cascade();
+            let fits = context
+                .monitors
+                .iter()
+                .any(|m| x + width <= m.x + m.width && y + height <= m.y + m.height);
+
+            // start over from the corner instead of piling up at the screen edge
+            match (fits, context.active_monitor()) {
+                (false, Some(monitor)) => (monitor.x + CASCADE_STEP, monitor.y + CASCADE_STEP),
+                _ => (x, y),
+            }
+        }
+        Placement::Corner(corner) => match context.active_monitor() {
+            Some(m) => match corner {
+                Corner::TopLeft => (m.x + CORNER_MARGIN, m.y + CORNER_MARGIN),
+                Corner::TopRight => (m.x + m.width - width - CORNER_MARGIN, m.y + CORNER_MARGIN),
+                Corner::BottomLeft => {
+                    (m.x + CORNER_MARGIN, m.y + m.height - height - CORNER_MARGIN)
+                }
+                Corner::BottomRight => (
+                    m.x + m.width - width - CORNER_MARGIN,
+                    m.y + m.height - height - CORNER_MARGIN,
+                ),
+            },
+            None => (0, 0),
+        },
+        Placement::Remember => context.remembered.unwrap_or_else(cascade),
+    };
+
+    clamp(
+        Bounds {
+            x,
+            y,
+            width,
+            height,
+        },
+        context.monitors,
+    )
+}
+
+/// Moves the window fully onto the monitor nearest to its center
+pub fn clamp(window: Bounds, monitors: &[Bounds]) -> (i32, i32) {
+    let center = (window.x + window.width / 2, window.y + window.height / 2);
+
+    let Some(monitor) = monitors
+        .iter()
+        .min_by_key(|m| m.distance_to(center.0, center.1))
+    else {
+        return (window.x, window.y);
+    };
+
+    (
+        window
+            .x
+            .min(monitor.x + monitor.width - window.width)
+            .max(monitor.x),
+        window
+            .y
+            .min(monitor.y + monitor.height - window.height)
+            .max(monitor.y),
+    )
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone};
+use tiny_skia::Pixmap;
+
+use crate::config::data_dir;
+use crate::render::{image_data, render_image};
+use crate::ClipboardContent;
+
+/// When a reminder pops up, picked from the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemindTime {
+    In(Duration),
+    /// 9:00 the next day
+    TomorrowMorning,
+}
+
+impl RemindTime {
+    pub const CHOICES: [RemindTime; 5] = [
+        RemindTime::In(Duration::from_secs(5 * 60)),
+        RemindTime::In(Duration::from_secs(15 * 60)),
+        RemindTime::In(Duration::from_secs(60 * 60)),
+        RemindTime::In(Duration::from_secs(3 * 60 * 60)),
+        RemindTime::TomorrowMorning,
+    ];
+
+    pub fn label(self) -> String {
+        match self {
+            RemindTime::In(duration) if duration.as_secs() >= 3600 => {
+                format!("In {} h", duration.as_secs() / 3600)
+            }
+            RemindTime::In(duration) => format!("In {} min", duration.as_secs() / 60),
+            RemindTime::TomorrowMorning => "Tomorrow at 9:00".to_string(),
+        }
+    }
+
+    pub fn due(self) -> DateTime<Local> {
+        let now = Local::now();
+        match self {
+            RemindTime::In(duration) => now + duration,
+            RemindTime::TomorrowMorning => now
+                .date_naive()
+                .checked_add_days(Days::new(1))
+                .map(|day| day.and_time(NaiveTime::from_hms_opt(9, 0, 0).unwrap()))
+                .and_then(|time| Local.from_local_datetime(&time).earliest())
+                .unwrap_or(now + Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
+/// A hidden note waiting for its time, saved to disk so it survives restarts
+#[derive(Debug)]
+pub struct Reminder {
+    pub due: DateTime<Local>,
+    file: Option<PathBuf>,
+}
+
+impl Reminder {
+    pub fn schedule(due: DateTime<Local>, content: &ClipboardContent) -> Self {
+        let file = save(due, content);
+        Reminder { due, file }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.due <= Local::now()
+    }
+
+    /// The due time on the event loop clock
+    pub fn instant(&self) -> Instant {
+        let left = (self.due - Local::now()).to_std().unwrap_or_default();
+        Instant::now() + left
+    }
+
+    /// Drops the saved copy once the reminder popped up
+    pub fn forget(&self) {
+        if let Some(file) = &self.file {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+fn reminders_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("reminders"))
+}
+
+/// Files are named `<due unix time>-<unique part>.<txt or png>`
+fn save(due: DateTime<Local>, content: &ClipboardContent) -> Option<PathBuf> {
+    let dir = reminders_dir()?;
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("can't save reminder: {e}");
+        return None;
+    }
+
+    let name = format!(
+        "{}-{}",
+        due.timestamp(),
+        Local::now().timestamp_nanos_opt().unwrap_or_default()
+    );
+
+    let (path, result) = match content {
+        ClipboardContent::Text(t) => {
+            let path = dir.join(name + ".txt");
+            let result = fs::write(&path, t).map_err(|e| e.to_string());
+            (path, result)
+        }
+        ClipboardContent::Image(i) => {
+            let path = dir.join(name + ".png");
+            let result = render_image(i.clone())
+                .save_png(&path)
+                .map_err(|e| e.to_string());
+            (path, result)
+        }
+        ClipboardContent::Timer(_) | ClipboardContent::None => return None,
+    };
+
+    match result {
+        Ok(()) => Some(path),
+        Err(e) => {
+            eprintln!("can't save reminder: {e}");
+            None
+        }
+    }
+}
+
+/// Reminders saved by earlier runs, overdue ones included
+pub fn load() -> Vec<(Reminder, ClipboardContent)> {
+    let Some(entries) = reminders_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut reminders: Vec<(Reminder, ClipboardContent)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path.file_stem()?.to_str()?;
+            let due = name.split('-').next()?.parse().ok()?;
+            let due = Local.timestamp_opt(due, 0).single()?;
+
+            let content = match path.extension()?.to_str()? {
+                "txt" => ClipboardContent::Text(fs::read_to_string(&path).ok()?),
+                "png" => ClipboardContent::Image(image_data(Pixmap::load_png(&path).ok()?)),
+                _ => return None,
+            };
+
+            Some((
+                Reminder {
+                    due,
+                    file: Some(path),
+                },
+                content,
+            ))
+        })
+        .collect();
+
+    reminders.sort_by_key(|(reminder, _)| reminder.due);
+    reminders
+}
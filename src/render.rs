@@ -0,0 +1,323 @@
+use arboard::ImageData;
+use fontdue::layout::{CoordinateSystem, Layout, TextStyle};
+use fontdue::Font;
+use tiny_skia::{
+    Color, ColorU8, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Point, Rect, Transform,
+};
+
+pub fn render_char(ch: char, size: f32, font: &Font, color: Color) -> Pixmap {
+    let (metrics, bitmap) = font.rasterize(ch, size);
+
+    if ch == ' ' {
+        return Pixmap::new(size as u32, size as u32).unwrap();
+    } else if ch == '\t' {
+        return Pixmap::new(size as u32 * 2, size as u32).unwrap();
+    }
+
+    let mut pixmap = Pixmap::new(metrics.width as u32, metrics.height as u32).unwrap();
+    let data: &mut [u8] = pixmap.data_mut();
+
+    for j in 0..(metrics.width * metrics.height) {
+        let mut color = color;
+        color.apply_opacity(bitmap[j] as f32 / 255.0);
+        let color = color.premultiply().to_color_u8();
+
+        data[j * 4] = color.red();
+        data[j * 4 + 1] = color.green();
+        data[j * 4 + 2] = color.blue();
+        data[j * 4 + 3] = color.alpha();
+    }
+
+    pixmap
+}
+
+pub fn render_text(text: String, font_size: f32, font: &Font, color: Color) -> Pixmap {
+    let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
+    layout.append(&[font], &TextStyle::new(&text, font_size, 0));
+
+    let mut text_width = 0;
+    let mut max_height = 0;
+
+    let mut chars: Vec<(Pixmap, f32, f32)> = Vec::new();
+
+    for gl in layout.glyphs() {
+        let ch = gl.parent;
+
+        let height = gl.y as i32 + gl.height as i32;
+
+        let rendered = render_char(ch, font_size, font, color);
+        text_width = gl.x as i32 + gl.width as i32;
+        if height > max_height {
+            max_height = height;
+        }
+        chars.push((rendered, gl.x, gl.y));
+    }
+
+    let mut pixmap = match Pixmap::new(text_width as u32, max_height as u32) {
+        Some(i) => i,
+        None => {
+            return Pixmap::new(1, 1).unwrap();
+        }
+    };
+    let paint = PixmapPaint::default();
+
+    for jujuk in chars {
+        let ele = jujuk.0;
+        let pos = (jujuk.1, jujuk.2);
+
+        pixmap.draw_pixmap(
+            pos.0 as i32,
+            pos.1 as i32,
+            ele.as_ref(),
+            &paint,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    pixmap
+}
+
+pub fn render_text_with_ln(
+    text: String,
+    size: f32,
+    font: &Font,
+    line_height: i32,
+    color: Color,
+) -> Pixmap {
+    let mut width = 0;
+    let mut lines: Vec<Pixmap> = Vec::new();
+    for ele in text.split("\n") {
+        let rendered = render_text(expand_tabs(ele, 4), size, font, color);
+        if rendered.width() > width {
+            width = rendered.width();
+        }
+        lines.push(rendered);
+    }
+    let height = line_height * lines.len() as i32;
+
+    let mut pixmap = Pixmap::new(width, height as u32).unwrap();
+    let paint = PixmapPaint::default();
+
+    let mut y: i32 = 0;
+    for ele in lines {
+        pixmap.draw_pixmap(
+            0,
+            y + (line_height - ele.height() as i32),
+            ele.as_ref(),
+            &paint,
+            Transform::identity(),
+            None,
+        );
+        y += line_height;
+    }
+
+    pixmap
+}
+
+/// Clipboard images have straight alpha, pixmaps are premultiplied
+pub fn render_image(image: ImageData<'static>) -> Pixmap {
+    let mut pixmap = Pixmap::new(image.width as u32, image.height as u32).unwrap();
+    for (pixel, rgba) in pixmap
+        .pixels_mut()
+        .iter_mut()
+        .zip(image.bytes.chunks_exact(4))
+    {
+        *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+    }
+    pixmap
+}
+
+/// The pixmap as clipboard image data, the other way around from `render_image`
+pub fn image_data(pixmap: Pixmap) -> ImageData<'static> {
+    let bytes: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    ImageData {
+        width: pixmap.width() as usize,
+        height: pixmap.height() as usize,
+        bytes: bytes.into(),
+    }
+}
+
+pub fn fill_rect(pixmap: &mut Pixmap, rect: Rect, color: Color) {
+    let path = PathBuilder::from_rect(rect);
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::EvenOdd,
+        Transform::identity(),
+        None,
+    );
+}
+
+pub fn text_width(text: &str, size: f32, font: &Font) -> f32 {
+    text.chars()
+        .map(|ch| font.metrics(ch, size).advance_width)
+        .sum()
+}
+
+/// Size of the text block as `render_text_with_ln` would draw it, without rasterizing
+pub fn measure_text(text: &str, size: f32, font: &Font, line_height: f32) -> (f32, f32) {
+    let width = text
+        .split('\n')
+        .map(|line| text_width(&expand_tabs(line, 4), size, font))
+        .fold(0.0, f32::max);
+    let lines = text.split('\n').count();
+
+    (width, line_height * lines as f32)
+}
+
+/// Cuts the text with an ellipsis so it fits into `max_width` pixels
+pub fn fit_text(text: &str, max_width: f32, size: f32, font: &Font) -> String {
+    if text_width(text, size, font) <= max_width {
+        return text.to_string();
+    }
+
+    let max_width = max_width - text_width("…", size, font);
+    let mut width = 0.0;
+    let mut fitted = String::new();
+
+    for ch in text.chars() {
+        width += font.metrics(ch, size).advance_width;
+        if width > max_width {
+            break;
+        }
+        fitted.push(ch);
+    }
+
+    fitted + "…"
+}
+
+pub fn rect_contains(rect: Rect, x: f32, y: f32) -> bool {
+    x >= rect.left() && x < rect.right() && y >= rect.top() && y < rect.bottom()
+}
+
+/// Draws the text with its baseline at `baseline`, returns how wide it is.
+/// Bold and italic are faked with an offset copy and a slant, there is one face per font
+#[allow(clippy::too_many_arguments)]
+pub fn draw_glyphs(
+    pixmap: &mut Pixmap,
+    text: &str,
+    x: f32,
+    baseline: f32,
+    size: f32,
+    font: &Font,
+    color: Color,
+    bold: bool,
+    italic: bool,
+) -> f32 {
+    let paint = PixmapPaint::default();
+    let bold_offset = if bold { (size / 20.0).max(0.5) } else { 0.0 };
+    let mut pen = x;
+
+    for ch in text.chars() {
+        let metrics = font.metrics(ch, size);
+        if !ch.is_whitespace() && metrics.width > 0 && metrics.height > 0 {
+            let glyph = render_char(ch, size, font, color);
+            let glyph_x = pen + metrics.xmin as f32;
+            let glyph_y = baseline - metrics.height as f32 - metrics.ymin as f32;
+
+            let transform = if italic {
+                // lean the glyph right around its bottom edge
+                Transform::from_row(
+                    1.0,
+                    0.0,
+                    -0.2,
+                    1.0,
+                    glyph_x + 0.2 * glyph.height() as f32,
+                    glyph_y,
+                )
+            } else {
+                Transform::from_translate(glyph_x, glyph_y)
+            };
+
+            pixmap.draw_pixmap(0, 0, glyph.as_ref(), &paint, transform, None);
+            if bold {
+                pixmap.draw_pixmap(
+                    0,
+                    0,
+                    glyph.as_ref(),
+                    &paint,
+                    transform.post_translate(bold_offset, 0.0),
+                    None,
+                );
+            }
+        }
+        pen += metrics.advance_width;
+    }
+
+    pen - x + bold_offset
+}
+
+/// Transform an image is drawn with, stretched over the content area
+pub fn image_transform(area: Rect, width: u32, height: u32) -> Transform {
+    Transform::from_row(
+        area.width() / width as f32,
+        0.0,
+        0.0,
+        area.height() / height as f32,
+        area.x(),
+        area.y(),
+    )
+}
+
+/// Window point mapped back onto the image, in image pixels
+pub fn image_point(area: Rect, width: u32, height: u32, x: f32, y: f32) -> Option<Point> {
+    let mut point = Point::from_xy(x, y);
+    image_transform(area, width, height)
+        .invert()?
+        .map_points(std::slice::from_mut(&mut point));
+    Some(point)
+}
+
+/// Replaces tabs with spaces up to the next tab stop, so indentation keeps its columns
+pub fn expand_tabs(line: &str, width: usize) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+
+    for ch in line.chars() {
+        if ch == '\t' {
+            let spaces = width - column % width;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            expanded.push(ch);
+            column += 1;
+        }
+    }
+
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_round_trip_keeps_straight_alpha() {
+        let bytes = vec![200, 100, 50, 255, 200, 100, 50, 128, 0, 0, 0, 0];
+        let image = ImageData {
+            width: 3,
+            height: 1,
+            bytes: bytes.clone().into(),
+        };
+
+        let pixmap = render_image(image);
+        assert_eq!(pixmap.pixel(1, 0).unwrap().red(), 100);
+
+        let back = image_data(pixmap);
+        for (got, want) in back.bytes.iter().zip(&bytes) {
+            assert!(got.abs_diff(*want) <= 1, "{got} != {want}");
+        }
+    }
+}
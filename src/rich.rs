@@ -0,0 +1,288 @@
+use fontdue::Font;
+use tiny_skia::{Color, Pixmap, Rect};
+
+use crate::render::{draw_glyphs, fill_rect, text_width};
+
+const PADDING: f32 = 10.0;
+const LINE_SPACING: f32 = 1.4;
+const MIN_FONT_SIZE: f32 = 6.0;
+
+/// Fonts styled text is drawn with
+pub struct Fonts<'a> {
+    pub regular: &'a Font,
+    pub mono: &'a Font,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    /// Text color, the theme's one when `None`
+    pub color: Option<Color>,
+    pub background: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+    pub mono: bool,
+}
+
+impl Style {
+    fn font<'a>(&self, fonts: &Fonts<'a>) -> &'a Font {
+        if self.mono {
+            fonts.mono
+        } else {
+            fonts.regular
+        }
+    }
+
+    fn width(&self, text: &str, size: f32, fonts: &Fonts) -> f32 {
+        let bold = if self.bold {
+            (size / 20.0).max(0.5)
+        } else {
+            0.0
+        };
+        text_width(text, size, self.font(fonts)) + bold
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, style: Style) -> Self {
+        Span {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+/// A row of styled text
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub spans: Vec<Span>,
+    /// Text size relative to the rest, for headings
+    pub scale: f32,
+    /// Left indent in text heights
+    pub indent: f32,
+    /// Extra indent of the wrapped parts, so they line up after a list bullet
+    pub hanging: f32,
+    /// Fills the whole row, for code blocks
+    pub background: Option<Color>,
+    /// Bar at the left edge, for quotes
+    pub bar: Option<Color>,
+    /// Horizontal line instead of text
+    pub rule: bool,
+    /// Long lines break at spaces instead of running off the note
+    pub wrap: bool,
+}
+
+impl Default for Line {
+    fn default() -> Self {
+        Line {
+            spans: Vec::new(),
+            scale: 1.0,
+            indent: 0.0,
+            hanging: 0.0,
+            background: None,
+            bar: None,
+            rule: false,
+            wrap: true,
+        }
+    }
+}
+
+impl Line {
+    pub fn new(spans: Vec<Span>) -> Self {
+        Line {
+            spans,
+            ..Line::default()
+        }
+    }
+
+    pub fn height(&self, size: f32) -> f32 {
+        size * self.scale * LINE_SPACING
+    }
+
+    pub fn width(&self, size: f32, fonts: &Fonts) -> f32 {
+        let size = size * self.scale;
+        size * self.indent
+            + self
+                .spans
+                .iter()
+                .map(|span| span.style.width(&span.text, size, fonts))
+                .sum::<f32>()
+    }
+
+    /// Breaks the line at spaces so every part fits into `max_width`
+    fn wrap(&self, max_width: f32, size: f32, fonts: &Fonts) -> Vec<Line> {
+        if !self.wrap || self.width(size, fonts) <= max_width {
+            return vec![self.clone()];
+        }
+
+        let text_size = size * self.scale;
+        let mut room = max_width - text_size * self.indent;
+        let empty = Line {
+            spans: Vec::new(),
+            indent: self.indent + self.hanging,
+            ..self.clone()
+        };
+
+        let mut lines = Vec::new();
+        let mut current = Line {
+            spans: Vec::new(),
+            ..self.clone()
+        };
+        let mut width = 0.0;
+
+        for span in &self.spans {
+            for word in span.text.split_inclusive(' ') {
+                let word_width = span.style.width(word, text_size, fonts);
+                if width + word_width > room && !current.spans.is_empty() {
+                    lines.push(std::mem::replace(&mut current, empty.clone()));
+                    room = max_width - text_size * empty.indent;
+                    width = 0.0;
+                }
+
+                width += word_width;
+                match current.spans.last_mut() {
+                    Some(last) if last.style == span.style => last.text.push_str(word),
+                    _ => current.spans.push(Span::new(word, span.style)),
+                }
+            }
+        }
+        lines.push(current);
+
+        lines
+    }
+}
+
+/// Biggest text size up to `max_size` the wrapped lines fit the area with
+pub fn fit(lines: &[Line], area: Rect, fonts: &Fonts, max_size: f32) -> (f32, Vec<Line>) {
+    let max_width = area.width() - PADDING * 2.0;
+    let max_height = area.height() - PADDING * 2.0;
+    let mut size = max_size;
+
+    loop {
+        let wrapped: Vec<Line> = lines
+            .iter()
+            .flat_map(|line| line.wrap(max_width, size, fonts))
+            .collect();
+        let height: f32 = wrapped.iter().map(|line| line.height(size)).sum();
+        // lines that don't wrap, like code, have to fit on their own
+        let fits_width = wrapped
+            .iter()
+            .filter(|line| !line.wrap)
+            .all(|line| line.width(size, fonts) <= max_width);
+
+        if (height <= max_height && fits_width) || size <= MIN_FONT_SIZE {
+            return (size, wrapped);
+        }
+        size = (size * 0.9).max(MIN_FONT_SIZE);
+    }
+}
+
+/// Row of every line in the area, from the top
+pub fn layout(lines: &[Line], area: Rect, size: f32) -> Vec<Rect> {
+    let mut y = area.y() + PADDING;
+    lines
+        .iter()
+        .filter_map(|line| {
+            let height = line.height(size);
+            let row = Rect::from_xywh(area.x() + PADDING, y, area.width() - PADDING * 2.0, height);
+            y += height;
+            row
+        })
+        .collect()
+}
+
+pub fn draw(
+    pixmap: &mut Pixmap,
+    area: Rect,
+    lines: &[Line],
+    size: f32,
+    fonts: &Fonts,
+    color: Color,
+) {
+    for (line, row) in lines.iter().zip(layout(lines, area, size)) {
+        if row.top() > area.bottom() {
+            break;
+        }
+        draw_line(pixmap, line, row, size, fonts, color);
+    }
+}
+
+fn draw_line(pixmap: &mut Pixmap, line: &Line, row: Rect, size: f32, fonts: &Fonts, color: Color) {
+    let size = size * line.scale;
+    let thin = (size / 14.0).max(1.0);
+
+    if let Some(background) = line.background {
+        if let Some(rect) = Rect::from_xywh(
+            row.x() - PADDING / 2.0,
+            row.y(),
+            row.width() + PADDING,
+            row.height(),
+        ) {
+            fill_rect(pixmap, rect, background);
+        }
+    }
+    if let Some(bar) = line.bar {
+        if let Some(rect) = Rect::from_xywh(row.x(), row.y(), (size / 5.0).max(2.0), row.height()) {
+            fill_rect(pixmap, rect, bar);
+        }
+    }
+    if line.rule {
+        if let Some(rect) =
+            Rect::from_xywh(row.x(), row.y() + row.height() / 2.0, row.width(), thin)
+        {
+            fill_rect(pixmap, rect, color);
+        }
+        return;
+    }
+
+    let metrics = fonts.regular.horizontal_line_metrics(size);
+    let (ascent, descent) = metrics.map_or((size * 0.8, -size * 0.2), |m| (m.ascent, m.descent));
+    let baseline = row.y() + (row.height() - ascent + descent) / 2.0 + ascent;
+
+    let mut x = row.x() + size * line.indent;
+    for span in &line.spans {
+        let style = span.style;
+        let span_color = style.color.unwrap_or(color);
+        let width = style.width(&span.text, size, fonts);
+
+        if let Some(background) = style.background {
+            if let Some(rect) =
+                Rect::from_xywh(x - 1.0, baseline - ascent, width + 2.0, ascent - descent)
+            {
+                fill_rect(pixmap, rect, background);
+            }
+        }
+
+        draw_glyphs(
+            pixmap,
+            &span.text,
+            x,
+            baseline,
+            size,
+            style.font(fonts),
+            span_color,
+            style.bold,
+            style.italic,
+        );
+
+        if style.underline {
+            if let Some(rect) = Rect::from_xywh(x, baseline + thin, width, thin) {
+                fill_rect(pixmap, rect, span_color);
+            }
+        }
+        if style.strike {
+            if let Some(rect) = Rect::from_xywh(x, baseline - ascent * 0.35, width, thin) {
+                fill_rect(pixmap, rect, span_color);
+            }
+        }
+
+        x += width;
+    }
+}
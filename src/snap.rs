@@ -0,0 +1,118 @@
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::monitor::MonitorHandle;
+
+/// Gap left between notes stuck next to each other or arranged into a grid
+const NOTE_SPACING: i32 = 8;
+
+/// Window or monitor rectangle in physical pixels
+#[derive(Debug, Clone, Copy)]
+pub struct Bounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Bounds {
+    pub fn new(position: PhysicalPosition<i32>, size: PhysicalSize<u32>) -> Self {
+        Bounds {
+            x: position.x,
+            y: position.y,
+            width: size.width as i32,
+            height: size.height as i32,
+        }
+    }
+
+    pub fn of_monitor(monitor: &MonitorHandle) -> Self {
+        Bounds::new(monitor.position(), monitor.size())
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Squared distance from the point to the closest point of the rectangle
+    pub fn distance_to(&self, x: i32, y: i32) -> i64 {
+        let dx = (self.x - x).max(x - self.right()).max(0) as i64;
+        let dy = (self.y - y).max(y - self.bottom()).max(0) as i64;
+        dx * dx + dy * dy
+    }
+
+    fn right(&self) -> i32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height
+    }
+}
+
+/// Position of the window after it's pulled to the closest monitor or note edge
+///
+/// Each axis snaps on its own, to the nearest edge closer than `distance`
+pub fn snap(window: Bounds, monitor: Bounds, others: &[Bounds], distance: i32) -> (i32, i32) {
+    let mut xs = vec![monitor.x, monitor.right() - window.width];
+    let mut ys = vec![monitor.y, monitor.bottom() - window.height];
+
+    for other in others {
+        // only notes next to the window along the other axis are worth sticking to
+        let overlaps_y =
+            window.y < other.bottom() + distance && window.bottom() > other.y - distance;
+        let overlaps_x = window.x < other.right() + distance && window.right() > other.x - distance;
+
+        if overlaps_y {
+            xs.extend([
+                other.x - window.width - NOTE_SPACING,
+                other.right() + NOTE_SPACING,
+                other.x,
+                other.right() - window.width,
+            ]);
+        }
+        if overlaps_x {
+            ys.extend([
+                other.y - window.height - NOTE_SPACING,
+                other.bottom() + NOTE_SPACING,
+                other.y,
+                other.bottom() - window.height,
+            ]);
+        }
+    }
+
+    (
+        snap_axis(window.x, &xs, distance),
+        snap_axis(window.y, &ys, distance),
+    )
+}
+
+fn snap_axis(value: i32, targets: &[i32], distance: i32) -> i32 {
+    targets
+        .iter()
+        .copied()
+        .filter(|target| (target - value).abs() <= distance)
+        .min_by_key(|target| (target - value).abs())
+        .unwrap_or(value)
+}
+
+/// Splits the monitor into a grid of `count` cells, row by row
+///
+/// The cells are spaced the same way snapping spaces notes, so they stay in place
+pub fn arrange(count: usize, monitor: Bounds) -> Vec<Bounds> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let columns = (count as f64).sqrt().ceil() as i32;
+    let rows = (count as i32 + columns - 1) / columns;
+
+    let width = (monitor.width - NOTE_SPACING * (columns - 1)) / columns;
+    let height = (monitor.height - NOTE_SPACING * (rows - 1)) / rows;
+
+    (0..count as i32)
+        .map(|i| Bounds {
+            x: monitor.x + (i % columns) * (width + NOTE_SPACING),
+            y: monitor.y + (i / columns) * (height + NOTE_SPACING),
+            width,
+            height,
+        })
+        .collect()
+}
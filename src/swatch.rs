@@ -0,0 +1,495 @@
+use fontdue::Font;
+use tiny_skia::{Color, Pixmap, Rect};
+
+use crate::render::{draw_glyphs, fill_rect, rect_contains, text_width};
+use crate::theme::Theme;
+
+const FONT_SIZE: f32 = 14.0;
+const ROW_HEIGHT: f32 = 24.0;
+const PADDING: f32 = 10.0;
+const FORMATS: [&str; 4] = ["HEX", "RGB", "HSL", "Name"];
+/// Size a swatch note opens with, the text alone would make it tiny
+pub const SIZE: (f32, f32) = (260.0, 220.0);
+
+/// CSS color names, for the closest one to a color
+const NAMED: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+/// A color code shown as a filled swatch with the color in a few formats
+#[derive(Debug, Clone)]
+pub struct Swatch {
+    pub rgba: [u8; 4],
+    /// Format row that was just copied
+    copied: Option<usize>,
+}
+
+impl Swatch {
+    /// Reads `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()` and `hsla()`
+    pub fn parse(text: &str) -> Option<Swatch> {
+        let text = text.trim().to_ascii_lowercase();
+        let rgba = match text.strip_prefix('#') {
+            Some(hex) => parse_hex(hex)?,
+            None => parse_function(&text)?,
+        };
+        Some(Swatch { rgba, copied: None })
+    }
+
+    /// Whether the text opens as a swatch right away. Short hex like `#123` or `#add`
+    /// is more often an issue number or a tag, it's only read when picked from the views
+    pub fn detect(text: &str) -> bool {
+        let short_hex = text
+            .trim()
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() != 6 && hex.len() != 8);
+        !short_hex && Swatch::parse(text).is_some()
+    }
+
+    /// The color as hex, RGB, HSL and the closest named color
+    pub fn formats(&self) -> [String; 4] {
+        let [r, g, b, a] = self.rgba;
+        let (h, s, l) = to_hsl(r, g, b);
+        let alpha = a as f32 / 255.0;
+
+        let (hex, rgb, hsl) = if a == 255 {
+            (
+                format!("#{r:02x}{g:02x}{b:02x}"),
+                format!("rgb({r}, {g}, {b})"),
+                format!("hsl({h}, {s}%, {l}%)"),
+            )
+        } else {
+            (
+                format!("#{r:02x}{g:02x}{b:02x}{a:02x}"),
+                format!("rgba({r}, {g}, {b}, {alpha:.2})"),
+                format!("hsla({h}, {s}%, {l}%, {alpha:.2})"),
+            )
+        };
+        [hex, rgb, hsl, closest_name(r, g, b).to_string()]
+    }
+
+    fn rows_top(area: Rect) -> f32 {
+        area.bottom() - PADDING - ROW_HEIGHT * FORMATS.len() as f32
+    }
+
+    /// Row of every format, under the swatch
+    pub fn layout(&self, area: Rect) -> Vec<Rect> {
+        let top = Swatch::rows_top(area);
+        (0..FORMATS.len())
+            .filter_map(|i| {
+                Rect::from_xywh(
+                    area.x() + PADDING,
+                    top + ROW_HEIGHT * i as f32,
+                    area.width() - PADDING * 2.0,
+                    ROW_HEIGHT,
+                )
+            })
+            .collect()
+    }
+
+    pub fn row_at(&self, area: Rect, x: f32, y: f32) -> Option<usize> {
+        self.layout(area)
+            .iter()
+            .position(|row| rect_contains(*row, x, y))
+    }
+
+    /// Marks the format as copied and returns it
+    pub fn click(&mut self, index: usize) -> Option<String> {
+        let format = self
+            .formats()
+            .get(index)?
+            .trim_start_matches("≈ ")
+            .to_string();
+        self.copied = Some(index);
+        Some(format)
+    }
+
+    pub fn draw(&self, pixmap: &mut Pixmap, area: Rect, font: &Font, theme: &Theme) {
+        let [r, g, b, a] = self.rgba;
+        let top = Swatch::rows_top(area);
+        if let Some(swatch) = Rect::from_ltrb(
+            area.left() + PADDING,
+            area.top() + PADDING,
+            area.right() - PADDING,
+            top - PADDING / 2.0,
+        ) {
+            fill_rect(pixmap, swatch, Color::from_rgba8(r, g, b, a));
+        }
+
+        let label_width = FORMATS
+            .iter()
+            .map(|label| text_width(label, FONT_SIZE, font))
+            .fold(0.0, f32::max);
+        let mut faded = theme.text;
+        faded.apply_opacity(0.6);
+
+        let metrics = font.horizontal_line_metrics(FONT_SIZE);
+        let (ascent, descent) = metrics.map_or((FONT_SIZE * 0.8, -FONT_SIZE * 0.2), |m| {
+            (m.ascent, m.descent)
+        });
+
+        let formats = self.formats();
+        for (i, row) in self.layout(area).into_iter().enumerate() {
+            let baseline = row.y() + (ROW_HEIGHT - ascent + descent) / 2.0 + ascent;
+            draw_glyphs(
+                pixmap,
+                FORMATS[i],
+                row.x(),
+                baseline,
+                FONT_SIZE,
+                font,
+                faded,
+                false,
+                false,
+            );
+
+            let x = row.x() + label_width + PADDING * 1.5;
+            let width = draw_glyphs(
+                pixmap,
+                &formats[i],
+                x,
+                baseline,
+                FONT_SIZE,
+                font,
+                theme.text,
+                false,
+                false,
+            );
+            if self.copied == Some(i) {
+                draw_glyphs(
+                    pixmap,
+                    "copied",
+                    x + width + PADDING,
+                    baseline,
+                    FONT_SIZE,
+                    font,
+                    faded,
+                    false,
+                    true,
+                );
+            }
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<[u8; 4]> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).unwrap();
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+
+    match hex.len() {
+        3 => Some([digit(0) * 17, digit(1) * 17, digit(2) * 17, 255]),
+        4 => Some([digit(0) * 17, digit(1) * 17, digit(2) * 17, digit(3) * 17]),
+        6 => Some([byte(0), byte(2), byte(4), 255]),
+        8 => Some([byte(0), byte(2), byte(4), byte(6)]),
+        _ => None,
+    }
+}
+
+/// `rgb(255, 136, 0)`, `rgb(255 136 0 / 50%)`, `hsl(32, 100%, 50%)` and the `a` variants
+fn parse_function(text: &str) -> Option<[u8; 4]> {
+    let (name, rest) = text.split_once('(')?;
+    let args: Vec<&str> = rest
+        .strip_suffix(')')?
+        .split([',', ' ', '/'])
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    if args.len() != 3 && args.len() != 4 {
+        return None;
+    }
+
+    let number = |arg: &str| -> Option<f32> { arg.trim_end_matches('%').parse().ok() };
+    let alpha = match args.get(3) {
+        Some(arg) if arg.ends_with('%') => number(arg)? / 100.0,
+        Some(arg) => number(arg)?,
+        None => 1.0,
+    };
+    let alpha = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    match name.trim() {
+        "rgb" | "rgba" => {
+            let channel = |arg: &str| -> Option<u8> {
+                let value = number(arg)?;
+                let value = if arg.ends_with('%') {
+                    value * 2.55
+                } else {
+                    value
+                };
+                Some(value.clamp(0.0, 255.0).round() as u8)
+            };
+            Some([
+                channel(args[0])?,
+                channel(args[1])?,
+                channel(args[2])?,
+                alpha,
+            ])
+        }
+        "hsl" | "hsla" => {
+            let hue = args[0].trim_end_matches("deg").parse::<f32>().ok()?;
+            let [r, g, b] = from_hsl(hue, number(args[1])? / 100.0, number(args[2])? / 100.0);
+            Some([r, g, b, alpha])
+        }
+        _ => None,
+    }
+}
+
+/// Hue in degrees, saturation and lightness in percent
+fn to_hsl(r: u8, g: u8, b: u8) -> (u32, u32, u32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+
+    if d == 0.0 {
+        return (0, 0, (l * 100.0).round() as u32);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+
+    (
+        (h * 60.0).round() as u32 % 360,
+        (s * 100.0).round() as u32,
+        (l * 100.0).round() as u32,
+    )
+}
+
+fn from_hsl(h: f32, s: f32, l: f32) -> [u8; 3] {
+    let (s, l) = (s.clamp(0.0, 1.0), l.clamp(0.0, 1.0));
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |v: f32| ((v + m) * 255.0).round() as u8;
+    [channel(r), channel(g), channel(b)]
+}
+
+/// Name of the nearest CSS color, `≈` marks one that isn't exact
+fn closest_name(r: u8, g: u8, b: u8) -> String {
+    let distance = |color: u32| {
+        let channel = |shift: u32, value: u8| {
+            let diff = ((color >> shift) & 0xff) as i32 - value as i32;
+            diff * diff
+        };
+        channel(16, r) + channel(8, g) + channel(0, b)
+    };
+
+    let (name, color) = NAMED
+        .iter()
+        .min_by_key(|(_, color)| distance(*color))
+        .unwrap();
+    if distance(*color) == 0 {
+        name.to_string()
+    } else {
+        format!("≈ {name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba(text: &str) -> Option<[u8; 4]> {
+        Swatch::parse(text).map(|swatch| swatch.rgba)
+    }
+
+    #[test]
+    fn parses_color_codes() {
+        assert_eq!(rgba("#ff8800"), Some([255, 136, 0, 255]));
+        assert_eq!(rgba(" #FF880080 "), Some([255, 136, 0, 128]));
+        assert_eq!(rgba("#f80"), Some([255, 136, 0, 255]));
+        assert_eq!(rgba("rgb(255, 136, 0)"), Some([255, 136, 0, 255]));
+        assert_eq!(rgba("rgb(255 136 0 / 50%)"), Some([255, 136, 0, 128]));
+        assert_eq!(rgba("rgba(100%, 0%, 0%, 0.5)"), Some([255, 0, 0, 128]));
+        assert_eq!(rgba("hsl(0, 100%, 50%)"), Some([255, 0, 0, 255]));
+        assert_eq!(rgba("hsla(120deg, 100%, 25%, 1)"), Some([0, 128, 0, 255]));
+
+        assert_eq!(rgba("ff8800"), None);
+        assert_eq!(rgba("#ff88"), Some([255, 255, 136, 136]));
+        assert_eq!(rgba("#ff88000"), None);
+        assert_eq!(rgba("rgb(1, 2)"), None);
+        assert_eq!(rgba("#ggg"), None);
+    }
+
+    #[test]
+    fn detects_only_long_hex() {
+        assert!(Swatch::detect("#ff8800"));
+        assert!(Swatch::detect("#ff880080"));
+        assert!(Swatch::detect("rgb(255, 136, 0)"));
+        for text in ["#123", "#add", "#beef", "see #123"] {
+            assert!(!Swatch::detect(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn lists_formats() {
+        let swatch = Swatch::parse("#ff0000").unwrap();
+        assert_eq!(
+            swatch.formats(),
+            ["#ff0000", "rgb(255, 0, 0)", "hsl(0, 100%, 50%)", "red"]
+        );
+        assert_eq!(closest_name(250, 0, 0), "≈ red");
+    }
+}
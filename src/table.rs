@@ -0,0 +1,301 @@
+use fontdue::Font;
+use tiny_skia::{Pixmap, Rect};
+
+use crate::render::{draw_glyphs, fill_rect, fit_text, rect_contains, text_width};
+use crate::theme::Theme;
+
+const PADDING: f32 = 10.0;
+const FONT_SIZE: f32 = 14.0;
+const ROW_HEIGHT: f32 = 22.0;
+const CELL_PADDING: f32 = 6.0;
+const MIN_COLUMN_WIDTH: f32 = 24.0;
+/// Columns are cut to this until they are resized by hand
+const MAX_COLUMN_WIDTH: f32 = 240.0;
+/// How close to a column edge dragging resizes the column
+const GRAB_DISTANCE: f32 = 4.0;
+
+/// Tab or comma separated text shown as a grid, the first row is the header
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+    /// Widths columns were dragged to
+    widths: Vec<Option<f32>>,
+    scroll: usize,
+    /// Row and column of the cell that was just copied
+    copied: Option<(usize, usize)>,
+}
+
+impl Table {
+    /// Reads TSV, or CSV when there are no tabs, `None` when the rows don't line up
+    pub fn parse(text: &str) -> Option<Table> {
+        let separator = if text.contains('\t') { '\t' } else { ',' };
+        let rows = split(text, separator);
+
+        let columns = rows.first()?.len();
+        if rows.len() < 2 || columns < 2 || rows.iter().any(|row| row.len() != columns) {
+            return None;
+        }
+
+        Some(Table {
+            widths: vec![None; columns],
+            rows,
+            scroll: 0,
+            copied: None,
+        })
+    }
+
+    /// Whether the text opens as a table right away. Tabs that line up are enough,
+    /// commas turn up in prose too so CSV needs a header and at least two rows under it
+    pub fn detect(text: &str) -> bool {
+        let Some(table) = Table::parse(text) else {
+            return false;
+        };
+        if text.contains('\t') {
+            return true;
+        }
+
+        table.rows.len() >= 3
+            && table.rows[0].iter().all(|cell| {
+                let cell = cell.trim();
+                !cell.is_empty()
+                    && cell.parse::<f64>().is_err()
+                    && cell.split_whitespace().count() <= 3
+                    && !cell.ends_with(['.', '!', '?'])
+            })
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.rows.len(), self.widths.len())
+    }
+
+    fn column_widths(&self, font: &Font) -> Vec<f32> {
+        self.widths
+            .iter()
+            .enumerate()
+            .map(|(column, resized)| {
+                resized.unwrap_or_else(|| {
+                    let longest = self
+                        .rows
+                        .iter()
+                        .map(|row| text_width(&row[column], FONT_SIZE, font))
+                        .fold(0.0, f32::max);
+                    (longest + CELL_PADDING * 2.0).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH)
+                })
+            })
+            .collect()
+    }
+
+    /// Left edge of every column and the right edge of the last one
+    fn column_edges(&self, area: Rect, font: &Font) -> Vec<f32> {
+        let mut x = area.x() + PADDING;
+        let mut edges = vec![x];
+        for width in self.column_widths(font) {
+            x += width;
+            edges.push(x);
+        }
+        edges
+    }
+
+    /// Rows that fit the area: the header, then the body from the scroll position
+    fn visible_rows(&self, area: Rect) -> Vec<usize> {
+        let fit = ((area.height() - PADDING * 2.0) / ROW_HEIGHT).max(1.0) as usize;
+        let body = (self.scroll + 1..self.rows.len()).take(fit.saturating_sub(1));
+        std::iter::once(0).chain(body).collect()
+    }
+
+    /// Row and column of the cell under the point
+    pub fn cell_at(&self, area: Rect, font: &Font, x: f32, y: f32) -> Option<(usize, usize)> {
+        let edges = self.column_edges(area, font);
+        let column = edges
+            .windows(2)
+            .position(|edge| x >= edge[0] && x < edge[1])?;
+
+        let slot = (y - area.y() - PADDING) / ROW_HEIGHT;
+        if slot < 0.0 || !rect_contains(area, x, y) {
+            return None;
+        }
+        let row = *self.visible_rows(area).get(slot as usize)?;
+        Some((row, column))
+    }
+
+    /// Column whose right edge is under the point, to resize it
+    pub fn edge_at(&self, area: Rect, font: &Font, x: f32, y: f32) -> Option<usize> {
+        let height = self.visible_rows(area).len() as f32 * ROW_HEIGHT;
+        let top = area.y() + PADDING;
+        if y < top || y > top + height {
+            return None;
+        }
+
+        let edges = self.column_edges(area, font);
+        (1..edges.len())
+            .find(|i| (x - edges[*i]).abs() <= GRAB_DISTANCE)
+            .map(|i| i - 1)
+    }
+
+    /// Left edge of the column, for dragging its right one
+    pub fn column_left(&self, area: Rect, font: &Font, column: usize) -> f32 {
+        self.column_edges(area, font)[column]
+    }
+
+    pub fn resize_column(&mut self, column: usize, width: f32) {
+        self.widths[column] = Some(width.max(MIN_COLUMN_WIDTH));
+    }
+
+    /// Marks the cell as copied and returns its text
+    pub fn click(&mut self, row: usize, column: usize) -> Option<String> {
+        let text = self.rows.get(row)?.get(column)?.clone();
+        self.copied = Some((row, column));
+        Some(text)
+    }
+
+    pub fn scroll_by(&mut self, rows: i32) {
+        let last = self.rows.len().saturating_sub(2) as i32;
+        self.scroll = (self.scroll as i32 + rows).clamp(0, last) as usize;
+    }
+
+    pub fn draw(&self, pixmap: &mut Pixmap, area: Rect, font: &Font, theme: &Theme) {
+        let edges = self.column_edges(area, font);
+        let right = edges[edges.len() - 1].min(area.right());
+        let rows = self.visible_rows(area);
+        let top = area.y() + PADDING;
+        let bottom = top + rows.len() as f32 * ROW_HEIGHT;
+
+        let mut header = theme.text;
+        header.apply_opacity(0.12);
+        if let Some(rect) =
+            Rect::from_points(&[(edges[0], top).into(), (right, top + ROW_HEIGHT).into()])
+        {
+            fill_rect(pixmap, rect, header);
+        }
+
+        let metrics = font.horizontal_line_metrics(FONT_SIZE);
+        let (ascent, descent) = metrics.map_or((FONT_SIZE * 0.8, -FONT_SIZE * 0.2), |m| {
+            (m.ascent, m.descent)
+        });
+
+        for (slot, row) in rows.iter().enumerate() {
+            let y = top + slot as f32 * ROW_HEIGHT;
+
+            for (column, edge) in edges.windows(2).enumerate() {
+                if edge[0] >= area.right() {
+                    break;
+                }
+
+                if self.copied == Some((*row, column)) {
+                    let mut copied = theme.accent;
+                    copied.apply_opacity(0.3);
+                    if let Some(rect) =
+                        Rect::from_xywh(edge[0], y, edge[1].min(right) - edge[0], ROW_HEIGHT)
+                    {
+                        fill_rect(pixmap, rect, copied);
+                    }
+                }
+
+                let room = edge[1].min(area.right()) - edge[0] - CELL_PADDING * 2.0;
+                let text = fit_text(&self.rows[*row][column], room, FONT_SIZE, font);
+                let baseline = y + (ROW_HEIGHT - ascent + descent) / 2.0 + ascent;
+                draw_glyphs(
+                    pixmap,
+                    &text,
+                    edge[0] + CELL_PADDING,
+                    baseline,
+                    FONT_SIZE,
+                    font,
+                    theme.text,
+                    *row == 0,
+                    false,
+                );
+            }
+        }
+
+        let mut grid = theme.text;
+        grid.apply_opacity(0.3);
+        for slot in 0..=rows.len() {
+            let y = top + slot as f32 * ROW_HEIGHT;
+            if let Some(rect) = Rect::from_xywh(edges[0], y, right - edges[0], 1.0) {
+                fill_rect(pixmap, rect, grid);
+            }
+        }
+        for x in edges.iter().take_while(|x| **x <= area.right()) {
+            if let Some(rect) = Rect::from_xywh(*x, top, 1.0, bottom - top) {
+                fill_rect(pixmap, rect, grid);
+            }
+        }
+    }
+}
+
+/// Splits the text into rows of fields, quoted fields may hold separators, quotes and newlines
+fn split(text: &str, separator: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            _ if quoted => field.push(ch),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ if ch == separator => row.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // a spreadsheet copy ends with a newline, blank lines aren't rows
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_fields() {
+        let rows = split("a,\"b, c\",\"say \"\"hi\"\"\"\r\n1,2,3\n\n", ',');
+        assert_eq!(rows, [vec!["a", "b, c", "say \"hi\""], vec!["1", "2", "3"]]);
+        assert_eq!(split("a\tb\n\"x\ny\"\tz", '\t')[1], ["x\ny", "z"]);
+    }
+
+    #[test]
+    fn parses_rows_that_line_up() {
+        let table = Table::parse("a\tb\n1\t2\n3\t4").unwrap();
+        assert_eq!(table.size(), (3, 2));
+        assert!(Table::parse("a,b\n1,2,3").is_none());
+        assert!(Table::parse("one column\nonly").is_none());
+        assert!(Table::parse("a,b").is_none());
+    }
+
+    #[test]
+    fn detects_tables() {
+        assert!(Table::detect("name,age,city\nBob,30,Paris\nAnn,25,Rome"));
+        assert!(Table::detect("a\tb\n1\t2"));
+    }
+
+    #[test]
+    fn prose_with_commas_is_not_a_table() {
+        let cases = [
+            "Hello, world\nBye, now",
+            "I went home, then slept.\nShe stayed, then left.\nWe all came, and ate.",
+            "1,2\n3,4\n5,6",
+            "name,age\nBob,30",
+        ];
+        for text in cases {
+            assert!(!Table::detect(text), "{text}");
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use tiny_skia::Color;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub background: Color,
+    pub text: Color,
+    pub accent: Color,
+    pub accent_text: Color,
+}
+
+impl Theme {
+    pub fn classic() -> Theme {
+        Theme {
+            background: Color::from_rgba8(250, 250, 120, 250),
+            text: Color::from_rgba8(255, 0, 0, 255),
+            accent: Color::from_rgba8(220, 80, 80, 150),
+            accent_text: Color::from_rgba8(220, 220, 220, 255),
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            background: Color::from_rgba8(248, 248, 245, 250),
+            text: Color::from_rgba8(30, 30, 30, 255),
+            accent: Color::from_rgba8(120, 120, 120, 150),
+            accent_text: Color::from_rgba8(250, 250, 250, 255),
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            background: Color::from_rgba8(35, 35, 40, 250),
+            text: Color::from_rgba8(225, 225, 225, 255),
+            accent: Color::from_rgba8(90, 90, 100, 180),
+            accent_text: Color::from_rgba8(230, 230, 230, 255),
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            background: Color::from_rgba8(0, 0, 0, 255),
+            text: Color::from_rgba8(255, 255, 0, 255),
+            accent: Color::from_rgba8(255, 255, 255, 255),
+            accent_text: Color::from_rgba8(0, 0, 0, 255),
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name {
+            "classic" | "sticky" => Some(Theme::classic()),
+            "light" => Some(Theme::light()),
+            "dark" => Some(Theme::dark()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Background of a note with the given tag, the tag color is mixed into the theme one
+    pub fn note_background(&self, tag: ColorTag) -> Color {
+        match tag.color() {
+            Some(color) => mix(self.background, color, 0.45),
+            None => self.background,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorTag {
+    #[default]
+    None,
+    Red,
+    Orange,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl ColorTag {
+    pub const ALL: [ColorTag; 6] = [
+        ColorTag::None,
+        ColorTag::Red,
+        ColorTag::Orange,
+        ColorTag::Green,
+        ColorTag::Blue,
+        ColorTag::Purple,
+    ];
+
+    pub fn color(self) -> Option<Color> {
+        match self {
+            ColorTag::None => None,
+            ColorTag::Red => Some(Color::from_rgba8(230, 70, 70, 255)),
+            ColorTag::Orange => Some(Color::from_rgba8(240, 150, 50, 255)),
+            ColorTag::Green => Some(Color::from_rgba8(80, 180, 90, 255)),
+            ColorTag::Blue => Some(Color::from_rgba8(70, 130, 230, 255)),
+            ColorTag::Purple => Some(Color::from_rgba8(160, 90, 210, 255)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorTag::None => "None",
+            ColorTag::Red => "Red",
+            ColorTag::Orange => "Orange",
+            ColorTag::Green => "Green",
+            ColorTag::Blue => "Blue",
+            ColorTag::Purple => "Purple",
+        }
+    }
+
+    pub fn next(self) -> ColorTag {
+        let index = ColorTag::ALL.iter().position(|t| *t == self).unwrap();
+        ColorTag::ALL[(index + 1) % ColorTag::ALL.len()]
+    }
+}
+
+pub fn mix(from: Color, to: Color, amount: f32) -> Color {
+    let channel = |a: f32, b: f32| a + (b - a) * amount;
+    Color::from_rgba(
+        channel(from.red(), to.red()),
+        channel(from.green(), to.green()),
+        channel(from.blue(), to.blue()),
+        from.alpha(),
+    )
+    .unwrap()
+}
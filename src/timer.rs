@@ -0,0 +1,309 @@
+use std::time::{Duration, Instant};
+
+use fontdue::Font;
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Transform};
+
+use crate::render::{fill_rect, render_text, text_width};
+use crate::theme::Theme;
+
+/// Height of the row with the start and reset buttons
+pub const CONTROLS_HEIGHT: f32 = 32.0;
+const BUTTON_WIDTH: f32 = 48.0;
+const STOPWATCH_TICK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerKind {
+    Countdown(Duration),
+    Stopwatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerButton {
+    StartPause,
+    Reset,
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    pub kind: TimerKind,
+    /// Time counted before the last start
+    counted: Duration,
+    started: Option<Instant>,
+    rang: bool,
+}
+
+impl Timer {
+    /// A running timer
+    pub fn new(kind: TimerKind) -> Self {
+        Timer {
+            kind,
+            counted: Duration::ZERO,
+            started: Some(Instant::now()),
+            rang: false,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        let elapsed = self.counted + self.started.map_or(Duration::ZERO, |s| now - s);
+        match self.kind {
+            TimerKind::Countdown(duration) => elapsed.min(duration),
+            TimerKind::Stopwatch => elapsed,
+        }
+    }
+
+    /// Time left of a countdown, time passed on a stopwatch
+    pub fn shown(&self, now: Instant) -> Duration {
+        match self.kind {
+            TimerKind::Countdown(duration) => duration - self.elapsed(now),
+            TimerKind::Stopwatch => self.elapsed(now),
+        }
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        matches!(self.kind, TimerKind::Countdown(duration) if self.elapsed(now) >= duration)
+    }
+
+    pub fn toggle(&mut self) {
+        let now = Instant::now();
+        if self.is_running() {
+            self.counted = self.elapsed(now);
+            self.started = None;
+        } else {
+            // starting a countdown that ran out starts it over
+            if self.is_finished(now) {
+                self.reset();
+            }
+            self.started = Some(now);
+        }
+    }
+
+    /// Stops the timer and winds it back to the start
+    pub fn reset(&mut self) {
+        self.counted = Duration::ZERO;
+        self.started = None;
+        self.rang = false;
+    }
+
+    /// Stops a countdown that ran out, returns true the first time it does
+    pub fn ring(&mut self, now: Instant) -> bool {
+        if !self.is_running() || !self.is_finished(now) {
+            return false;
+        }
+
+        self.counted = self.elapsed(now);
+        self.started = None;
+        !std::mem::replace(&mut self.rang, true)
+    }
+
+    /// When the shown time changes next, `None` while stopped
+    pub fn next_tick(&self, now: Instant) -> Option<Instant> {
+        self.started?;
+
+        Some(match self.kind {
+            TimerKind::Countdown(_) => {
+                // the countdown shows whole seconds rounded up, so it changes on the second
+                let left = self.shown(now);
+                let into_second = Duration::from_nanos(left.as_nanos() as u64 % 1_000_000_000);
+                now + if into_second.is_zero() {
+                    Duration::from_secs(1).min(left)
+                } else {
+                    into_second
+                }
+            }
+            TimerKind::Stopwatch => now + STOPWATCH_TICK,
+        })
+    }
+
+    pub fn text(&self, now: Instant) -> String {
+        match self.kind {
+            TimerKind::Countdown(_) => {
+                let shown = self.shown(now);
+                let secs = shown.as_secs() + u64::from(shown.subsec_nanos() > 0);
+                clock(secs)
+            }
+            TimerKind::Stopwatch => {
+                let shown = self.shown(now);
+                format!("{}.{}", clock(shown.as_secs()), shown.subsec_millis() / 100)
+            }
+        }
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on
+fn clock(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Reads a countdown length like `90`, `90s`, `5m`, `1h`, `1:30` or `1:00:00`
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+
+    let secs = if text.contains(':') {
+        text.split(':').try_fold(0u64, |secs, part| {
+            secs.checked_mul(60)?
+                .checked_add(part.trim().parse::<u64>().ok()?)
+        })?
+    } else {
+        let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+            Some(index) => text.split_at(index),
+            None => (text, ""),
+        };
+        let number: u64 = number.parse().ok()?;
+        match unit.trim() {
+            "" | "s" | "sec" => number,
+            "m" | "min" => number.checked_mul(60)?,
+            "h" => number.checked_mul(3600)?,
+            _ => return None,
+        }
+    };
+
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Places the buttons at the bottom of the content area
+pub fn layout(area: Rect) -> Vec<(TimerButton, Rect)> {
+    let y = area.bottom() - CONTROLS_HEIGHT;
+    let x = area.x() + area.width() / 2.0;
+    [
+        (TimerButton::StartPause, x - BUTTON_WIDTH - 4.0),
+        (TimerButton::Reset, x + 4.0),
+    ]
+    .into_iter()
+    .filter_map(|(button, x)| {
+        Some((
+            button,
+            Rect::from_xywh(x, y + 4.0, BUTTON_WIDTH, CONTROLS_HEIGHT - 8.0)?,
+        ))
+    })
+    .collect()
+}
+
+pub fn draw(pixmap: &mut Pixmap, area: Rect, timer: &Timer, font: &Font, theme: &Theme) {
+    let now = Instant::now();
+    let text = timer.text(now);
+    let color = if timer.is_finished(now) {
+        theme.accent
+    } else {
+        theme.text
+    };
+
+    // digits take all the room above the buttons
+    let digits_height = area.height() - CONTROLS_HEIGHT;
+    let width = text_width(&text, 100.0, font).max(1.0);
+    let size = (area.width() * 0.9 / width * 100.0)
+        .min(digits_height * 0.8)
+        .max(1.0);
+
+    let digits = render_text(text, size, font, color);
+    pixmap.draw_pixmap(
+        (area.x() + (area.width() - digits.width() as f32) / 2.0) as i32,
+        (area.y() + (digits_height - digits.height() as f32) / 2.0) as i32,
+        digits.as_ref(),
+        &PixmapPaint::default(),
+        Transform::identity(),
+        None,
+    );
+
+    for (button, rect) in layout(area) {
+        fill_rect(pixmap, rect, theme.accent);
+        draw_icon(pixmap, button, rect, timer.is_running(), theme.accent_text);
+    }
+}
+
+fn draw_icon(pixmap: &mut Pixmap, button: TimerButton, rect: Rect, running: bool, color: Color) {
+    let (cx, cy) = (
+        rect.x() + rect.width() / 2.0,
+        rect.y() + rect.height() / 2.0,
+    );
+    let r = rect.height() * 0.3;
+
+    let mut path = PathBuilder::new();
+    match button {
+        TimerButton::StartPause if running => {
+            path.push_rect(Rect::from_xywh(cx - r, cy - r, r * 0.7, r * 2.0).unwrap());
+            path.push_rect(Rect::from_xywh(cx + r * 0.3, cy - r, r * 0.7, r * 2.0).unwrap());
+        }
+        TimerButton::StartPause => {
+            path.move_to(cx - r * 0.8, cy - r);
+            path.line_to(cx + r, cy);
+            path.line_to(cx - r * 0.8, cy + r);
+            path.close();
+        }
+        TimerButton::Reset => {
+            path.push_rect(Rect::from_xywh(cx - r, cy - r, r * 2.0, r * 2.0).unwrap());
+        }
+    }
+
+    let Some(path) = path.finish() else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = true;
+    pixmap.fill_path(
+        &path,
+        &paint,
+        FillRule::Winding,
+        Transform::identity(),
+        None,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        let cases = [
+            ("90", 90),
+            ("90s", 90),
+            (" 5m ", 300),
+            ("5 min", 300),
+            ("1h", 3600),
+            ("1:30", 90),
+            ("1:00:00", 3600),
+        ];
+        for (text, secs) in cases {
+            assert_eq!(
+                parse_duration(text),
+                Some(Duration::from_secs(secs)),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_other_text() {
+        for text in ["", "0", "0:00", "5 days", "abc", "1:x", "-5", "1.5m"] {
+            assert_eq!(parse_duration(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn rejects_overflowing_durations() {
+        for text in [
+            "99999999999999999999",
+            "18446744073709551615m",
+            "6000000000000000h",
+            "1:1:1:1:1:1:1:1:1:1:1:1:1:1",
+        ] {
+            assert_eq!(parse_duration(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn shows_clock() {
+        assert_eq!(clock(59), "0:59");
+        assert_eq!(clock(3661), "1:01:01");
+    }
+}
@@ -0,0 +1,235 @@
+use fontdue::Font;
+use tiny_skia::{
+    Color, FillRule, LineCap, Paint, PathBuilder, Pixmap, PixmapPaint, Rect, Stroke, Transform,
+};
+use winit::window::WindowLevel;
+
+use crate::menu::{level_name, Action};
+use crate::render::{fill_rect, fit_text, render_text, text_width};
+use crate::theme::Theme;
+
+pub const TITLE_BAR_HEIGHT: f32 = 24.0;
+const TITLE_FONT_SIZE: f32 = 13.0;
+const TOOLTIP_FONT_SIZE: f32 = 12.0;
+
+/// Size of the lone close button drawn when the title bar is turned off
+const CLOSE_SIZE: f32 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleButton {
+    Pin,
+    Copy,
+    Collapse,
+    Close,
+}
+
+impl TitleButton {
+    /// Buttons in the order they are placed, from the right edge of the window
+    const ORDER: [TitleButton; 4] = [
+        TitleButton::Close,
+        TitleButton::Collapse,
+        TitleButton::Copy,
+        TitleButton::Pin,
+    ];
+
+    pub fn action(self) -> Action {
+        match self {
+            TitleButton::Pin => Action::ChangeLevel,
+            TitleButton::Copy => Action::Copy,
+            TitleButton::Collapse => Action::Collapse,
+            TitleButton::Close => Action::Close,
+        }
+    }
+
+    pub fn tooltip(self, level: WindowLevel, collapsed: bool) -> String {
+        match self {
+            TitleButton::Pin => format!("Level: {}", level_name(level)),
+            TitleButton::Copy => "Copy to clipboard".to_string(),
+            TitleButton::Collapse if collapsed => "Expand".to_string(),
+            TitleButton::Collapse => "Collapse".to_string(),
+            TitleButton::Close => "Close".to_string(),
+        }
+    }
+}
+
+/// Places the buttons for a window of given width
+pub fn layout(width: f32, title_bar: bool) -> Vec<(TitleButton, Rect)> {
+    if !title_bar {
+        return vec![(
+            TitleButton::Close,
+            Rect::from_xywh(width - CLOSE_SIZE, 0.0, CLOSE_SIZE, CLOSE_SIZE).unwrap(),
+        )];
+    }
+
+    TitleButton::ORDER
+        .iter()
+        .enumerate()
+        .map(|(i, button)| {
+            let x = width - TITLE_BAR_HEIGHT * (i + 1) as f32;
+            (
+                *button,
+                Rect::from_xywh(x, 0.0, TITLE_BAR_HEIGHT, TITLE_BAR_HEIGHT).unwrap(),
+            )
+        })
+        .collect()
+}
+
+pub struct TitleBarState<'a> {
+    pub title: &'a str,
+    pub hovered: Option<TitleButton>,
+    pub level: WindowLevel,
+    pub collapsed: bool,
+    pub title_bar: bool,
+}
+
+pub fn draw(pixmap: &mut Pixmap, font: &Font, theme: &Theme, state: &TitleBarState) {
+    let width = pixmap.width() as f32;
+    let buttons = layout(width, state.title_bar);
+
+    if state.title_bar {
+        fill_rect(
+            pixmap,
+            Rect::from_xywh(0.0, 0.0, width, TITLE_BAR_HEIGHT).unwrap(),
+            theme.accent,
+        );
+
+        let max_width = width - TITLE_BAR_HEIGHT * buttons.len() as f32 - 12.0;
+        if max_width > 0.0 {
+            let title = fit_text(state.title, max_width, TITLE_FONT_SIZE, font);
+            let text = render_text(title, TITLE_FONT_SIZE, font, theme.accent_text);
+            pixmap.draw_pixmap(
+                6,
+                ((TITLE_BAR_HEIGHT - text.height() as f32) / 2.0) as i32,
+                text.as_ref(),
+                &PixmapPaint::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    for (button, rect) in buttons {
+        if !state.title_bar {
+            fill_rect(pixmap, rect, theme.accent);
+        }
+        if state.hovered == Some(button) {
+            let mut highlight = theme.accent_text;
+            highlight.apply_opacity(0.3);
+            fill_rect(pixmap, rect, highlight);
+        }
+        draw_icon(pixmap, button, rect, theme.accent_text, state);
+    }
+}
+
+pub fn draw_tooltip(pixmap: &mut Pixmap, font: &Font, theme: &Theme, state: &TitleBarState) {
+    let Some(hovered) = state.hovered else {
+        return;
+    };
+    let Some((_, rect)) = layout(pixmap.width() as f32, state.title_bar)
+        .into_iter()
+        .find(|(button, _)| *button == hovered)
+    else {
+        return;
+    };
+
+    let text = hovered.tooltip(state.level, state.collapsed);
+    let width = text_width(&text, TOOLTIP_FONT_SIZE, font) + 10.0;
+    let height = TOOLTIP_FONT_SIZE + 8.0;
+    let x = (rect.right() - width).max(0.0);
+    let y = rect.bottom() + 2.0;
+
+    if let Some(background) = Rect::from_xywh(x, y, width, height) {
+        fill_rect(pixmap, background, theme.text);
+    }
+
+    let text = render_text(text, TOOLTIP_FONT_SIZE, font, theme.background);
+    pixmap.draw_pixmap(
+        x as i32 + 5,
+        (y + (height - text.height() as f32) / 2.0) as i32,
+        text.as_ref(),
+        &PixmapPaint::default(),
+        Transform::identity(),
+        None,
+    );
+}
+
+fn draw_icon(
+    pixmap: &mut Pixmap,
+    button: TitleButton,
+    rect: Rect,
+    color: Color,
+    state: &TitleBarState,
+) {
+    let (cx, cy) = (
+        rect.x() + rect.width() / 2.0,
+        rect.y() + rect.height() / 2.0,
+    );
+    let r = rect.width() * 0.25;
+
+    let mut path = PathBuilder::new();
+    match button {
+        TitleButton::Close => {
+            let r = if state.title_bar {
+                r
+            } else {
+                rect.width() * 0.35
+            };
+            path.move_to(cx - r, cy - r);
+            path.line_to(cx + r, cy + r);
+            path.move_to(cx + r, cy - r);
+            path.line_to(cx - r, cy + r);
+        }
+        TitleButton::Collapse => {
+            if state.collapsed {
+                path.move_to(cx - r, cy - r / 2.0);
+                path.line_to(cx, cy + r / 2.0);
+                path.line_to(cx + r, cy - r / 2.0);
+            } else {
+                path.move_to(cx - r, cy + r / 2.0);
+                path.line_to(cx, cy - r / 2.0);
+                path.line_to(cx + r, cy + r / 2.0);
+            }
+        }
+        TitleButton::Copy => {
+            path.push_rect(Rect::from_xywh(cx - r, cy - r, r * 1.4, r * 1.4).unwrap());
+            path.push_rect(
+                Rect::from_xywh(cx - r + r * 0.6, cy - r + r * 0.6, r * 1.4, r * 1.4).unwrap(),
+            );
+        }
+        TitleButton::Pin => {
+            path.push_circle(cx, cy - r * 0.4, r * 0.6);
+            path.move_to(cx, cy + r * 0.2);
+            path.line_to(cx, cy + r * 1.2);
+            if state.level == WindowLevel::AlwaysOnBottom {
+                path.move_to(cx - r, cy + r * 1.2);
+                path.line_to(cx + r, cy + r * 1.2);
+            }
+        }
+    }
+
+    let Some(path) = path.finish() else {
+        return;
+    };
+
+    let mut paint = Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = true;
+
+    if button == TitleButton::Pin && state.level == WindowLevel::AlwaysOnTop {
+        let circle = PathBuilder::from_circle(cx, cy - r * 0.4, r * 0.6).unwrap();
+        pixmap.fill_path(
+            &circle,
+            &paint,
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    let stroke = Stroke {
+        width: if state.title_bar { 1.5 } else { 2.5 },
+        line_cap: LineCap::Round,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+}
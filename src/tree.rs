@@ -0,0 +1,883 @@
+use std::collections::HashSet;
+
+use fontdue::Font;
+use tiny_skia::{Pixmap, PixmapPaint, Rect, Transform};
+
+use crate::code::Token;
+use crate::render::{fill_rect, fit_text, rect_contains, render_text};
+use crate::rich::{self, Fonts, Line, Span, Style};
+use crate::theme::Theme;
+
+const FONT_SIZE: f32 = 14.0;
+const STATUS_FONT_SIZE: f32 = 12.0;
+const STATUS_HEIGHT: f32 = 20.0;
+const INDENT: &str = "  ";
+
+/// Parsed JSON, YAML and XML all end up as this
+#[derive(Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+#[derive(Debug, Clone)]
+enum RowKind {
+    /// Opening or closing line of an object or array, clicking it folds the node
+    Branch,
+    /// A value, clicking it copies this text
+    Leaf(String),
+}
+
+/// Key, value and path of an entry of an object or array
+type Child<'a> = (Option<&'a str>, &'a Value, String);
+
+#[derive(Debug, Clone)]
+struct Row {
+    path: String,
+    kind: RowKind,
+    parts: Vec<(Token, String)>,
+}
+
+/// Structured data shown pretty-printed, with folded nodes and scrolling
+#[derive(Debug, Clone)]
+pub struct Tree {
+    root: Value,
+    folded: HashSet<String>,
+    /// Rows as they are shown, built again when a node is folded or unfolded
+    rows: Vec<Row>,
+    scroll: usize,
+    hovered: Option<usize>,
+    /// Row whose value was just copied
+    copied: Option<usize>,
+}
+
+impl Tree {
+    /// Reads JSON, XML or YAML, `None` when the text is none of them
+    pub fn parse(text: &str) -> Option<Tree> {
+        let root = parse_json(text)
+            .or_else(|| parse_xml(text))
+            .or_else(|| parse_yaml(text))?;
+
+        let mut tree = Tree {
+            root,
+            folded: HashSet::new(),
+            rows: Vec::new(),
+            scroll: 0,
+            hovered: None,
+            copied: None,
+        };
+        tree.rows = tree.build_rows();
+        Some(tree)
+    }
+
+    /// Whether the text is JSON or XML, which open as a tree right away
+    pub fn detect(text: &str) -> bool {
+        matches!(
+            parse_json(text).or_else(|| parse_xml(text)),
+            Some(Value::Object(_) | Value::Array(_))
+        )
+    }
+
+    fn build_rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        self.push_rows(&mut rows, None, &self.root, "$".to_string(), 0, true);
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        rows: &mut Vec<Row>,
+        key: Option<&str>,
+        value: &Value,
+        path: String,
+        depth: usize,
+        last: bool,
+    ) {
+        let mut parts = vec![(Token::Plain, INDENT.repeat(depth))];
+        if let Some(key) = key {
+            parts.push((Token::Key, quote(key)));
+            parts.push((Token::Plain, ": ".to_string()));
+        }
+        let comma = if last { "" } else { "," };
+
+        let (open, close, children): (&str, &str, Vec<Child>) = match value {
+            Value::Object(entries) => (
+                "{",
+                "}",
+                entries
+                    .iter()
+                    .map(|(key, value)| (Some(key.as_str()), value, child_path(&path, key)))
+                    .collect(),
+            ),
+            Value::Array(items) => (
+                "[",
+                "]",
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| (None, value, format!("{path}[{i}]")))
+                    .collect(),
+            ),
+            _ => {
+                let (token, text) = scalar(value);
+                parts.push((token, text));
+                parts.push((Token::Plain, comma.to_string()));
+                rows.push(Row {
+                    kind: RowKind::Leaf(copy_text(value)),
+                    path,
+                    parts,
+                });
+                return;
+            }
+        };
+
+        if children.is_empty() {
+            parts.push((Token::Plain, format!("{open}{close}{comma}")));
+            rows.push(Row {
+                kind: RowKind::Leaf(format!("{open}{close}")),
+                path,
+                parts,
+            });
+            return;
+        }
+
+        if self.folded.contains(&path) {
+            parts.push((Token::Plain, format!("{open} … {close}{comma}")));
+            parts.push((Token::Comment, format!("  {} items", children.len())));
+            rows.push(Row {
+                kind: RowKind::Branch,
+                path,
+                parts,
+            });
+            return;
+        }
+
+        parts.push((Token::Plain, open.to_string()));
+        rows.push(Row {
+            kind: RowKind::Branch,
+            path: path.clone(),
+            parts,
+        });
+
+        let count = children.len();
+        for (i, (key, value, child)) in children.into_iter().enumerate() {
+            self.push_rows(rows, key, value, child, depth + 1, i + 1 == count);
+        }
+
+        rows.push(Row {
+            kind: RowKind::Branch,
+            path,
+            parts: vec![(
+                Token::Plain,
+                format!("{}{close}{comma}", INDENT.repeat(depth)),
+            )],
+        });
+    }
+
+    /// Part of the content area the rows go in, the path is shown under it
+    fn rows_area(area: Rect) -> Option<Rect> {
+        Rect::from_xywh(
+            area.x(),
+            area.y(),
+            area.width(),
+            area.height() - STATUS_HEIGHT,
+        )
+    }
+
+    fn visible_lines(&self, rows: &[Row]) -> Vec<Line> {
+        rows.iter()
+            .skip(self.scroll)
+            .map(|row| Line {
+                spans: row
+                    .parts
+                    .iter()
+                    .map(|(_, text)| Span::new(text.clone(), Style::default()))
+                    .collect(),
+                wrap: false,
+                ..Line::default()
+            })
+            .collect()
+    }
+
+    /// Row under the point, counted from the first row
+    pub fn row_at(&self, area: Rect, x: f32, y: f32) -> Option<usize> {
+        let area = Tree::rows_area(area)?;
+        let lines = self.visible_lines(&self.rows);
+
+        rich::layout(&lines, area, FONT_SIZE)
+            .iter()
+            .position(|row| row.bottom() <= area.bottom() && rect_contains(*row, x, y))
+            .map(|index| index + self.scroll)
+    }
+
+    /// Folds or unfolds a branch, returns the value of a leaf to be copied
+    pub fn click(&mut self, index: usize) -> Option<String> {
+        let row = self.rows.get(index)?;
+        match &row.kind {
+            RowKind::Branch => {
+                let path = row.path.clone();
+                if !self.folded.remove(&path) {
+                    self.folded.insert(path);
+                }
+                self.rows = self.build_rows();
+                self.copied = None;
+                // folding takes rows away, the hovered one may be gone
+                self.hovered = self.hovered.filter(|i| *i < self.rows.len());
+                self.scroll = self.scroll.min(self.rows.len().saturating_sub(1));
+                None
+            }
+            RowKind::Leaf(text) => {
+                let text = text.clone();
+                self.copied = Some(index);
+                Some(text)
+            }
+        }
+    }
+
+    pub fn hover(&mut self, index: Option<usize>) -> bool {
+        if self.hovered == index {
+            return false;
+        }
+        self.hovered = index;
+        self.copied = None;
+        true
+    }
+
+    pub fn scroll_by(&mut self, rows: i32) {
+        let last = self.rows.len().saturating_sub(1) as i32;
+        self.scroll = (self.scroll as i32 + rows).clamp(0, last) as usize;
+    }
+
+    pub fn draw(&self, pixmap: &mut Pixmap, area: Rect, fonts: &Fonts, theme: &Theme) {
+        let Some(rows_area) = Tree::rows_area(area) else {
+            return;
+        };
+
+        let lines: Vec<Line> = self
+            .rows
+            .iter()
+            .skip(self.scroll)
+            .map(|row| Line {
+                spans: row
+                    .parts
+                    .iter()
+                    .map(|(token, text)| {
+                        Span::new(
+                            text.clone(),
+                            Style {
+                                color: token.color(theme),
+                                mono: true,
+                                ..Style::default()
+                            },
+                        )
+                    })
+                    .collect(),
+                wrap: false,
+                ..Line::default()
+            })
+            .collect();
+
+        let layout = rich::layout(&lines, rows_area, FONT_SIZE);
+        if let Some(hovered) = self.hovered.and_then(|i| i.checked_sub(self.scroll)) {
+            if let Some(row) = layout.get(hovered) {
+                let mut highlight = theme.text;
+                highlight.apply_opacity(0.1);
+                fill_rect(pixmap, *row, highlight);
+            }
+        }
+
+        // rows are cut off above the status line
+        let visible = layout
+            .iter()
+            .take_while(|row| row.bottom() <= rows_area.bottom())
+            .count();
+        rich::draw(
+            pixmap,
+            rows_area,
+            &lines[..visible],
+            FONT_SIZE,
+            fonts,
+            theme.text,
+        );
+
+        let hovered = self.hovered.and_then(|index| self.rows.get(index));
+        let status = match (hovered, self.copied) {
+            (Some(row), Some(_)) => format!("Copied {}", row.path),
+            (Some(row), None) => row.path.clone(),
+            (None, _) => "Click to fold, click a value to copy it".to_string(),
+        };
+        draw_status(pixmap, area, &status, fonts.regular, theme);
+    }
+}
+
+fn draw_status(pixmap: &mut Pixmap, area: Rect, status: &str, font: &Font, theme: &Theme) {
+    let Some(strip) = Rect::from_xywh(
+        area.x(),
+        area.bottom() - STATUS_HEIGHT,
+        area.width(),
+        STATUS_HEIGHT,
+    ) else {
+        return;
+    };
+
+    let mut background = theme.text;
+    background.apply_opacity(0.1);
+    fill_rect(pixmap, strip, background);
+
+    let status = fit_text(status, strip.width() - 12.0, STATUS_FONT_SIZE, font);
+    let text = render_text(status, STATUS_FONT_SIZE, font, theme.text);
+    pixmap.draw_pixmap(
+        strip.x() as i32 + 6,
+        (strip.y() + (STATUS_HEIGHT - text.height() as f32) / 2.0) as i32,
+        text.as_ref(),
+        &PixmapPaint::default(),
+        Transform::identity(),
+        None,
+    );
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '@' || c == '#');
+    if plain {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}[{}]", quote(key))
+    }
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(ch),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn scalar(value: &Value) -> (Token, String) {
+    match value {
+        Value::Null => (Token::Keyword, "null".to_string()),
+        Value::Bool(b) => (Token::Keyword, b.to_string()),
+        Value::Number(n) => (Token::Number, n.clone()),
+        Value::String(s) => (Token::String, quote(s)),
+        _ => (Token::Plain, String::new()),
+    }
+}
+
+/// Strings are copied without quotes, everything else as it's shown
+fn copy_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => scalar(value).1,
+    }
+}
+
+fn parse_json(text: &str) -> Option<Value> {
+    let mut parser = Json {
+        text: text.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    (parser.pos == parser.text.len()).then_some(value)
+}
+
+struct Json<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Json<'_> {
+    fn whitespace(&mut self) {
+        while self.text.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.whitespace();
+        let found = self.text.get(self.pos) == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.whitespace();
+        match self.text.get(self.pos)? {
+            b'{' => {
+                self.pos += 1;
+                let mut entries = Vec::new();
+                if self.eat(b'}') {
+                    return Some(Value::Object(entries));
+                }
+                loop {
+                    self.whitespace();
+                    let key = self.string()?;
+                    if !self.eat(b':') {
+                        return None;
+                    }
+                    entries.push((key, self.value()?));
+                    if self.eat(b'}') {
+                        return Some(Value::Object(entries));
+                    }
+                    if !self.eat(b',') {
+                        return None;
+                    }
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if self.eat(b']') {
+                    return Some(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    if self.eat(b']') {
+                        return Some(Value::Array(items));
+                    }
+                    if !self.eat(b',') {
+                        return None;
+                    }
+                }
+            }
+            b'"' => self.string().map(Value::String),
+            _ => {
+                for (word, value) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if self.text[self.pos..].starts_with(word.as_bytes()) {
+                        self.pos += word.len();
+                        return Some(value);
+                    }
+                }
+
+                let start = self.pos;
+                while self
+                    .text
+                    .get(self.pos)
+                    .is_some_and(|b| b"+-0123456789.eE".contains(b))
+                {
+                    self.pos += 1;
+                }
+                let number = std::str::from_utf8(&self.text[start..self.pos]).ok()?;
+                number
+                    .parse::<f64>()
+                    .is_ok()
+                    .then(|| Value::Number(number.to_string()))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.text.get(self.pos) != Some(&b'"') {
+            return None;
+        }
+        self.pos += 1;
+
+        let mut bytes = Vec::new();
+        loop {
+            let byte = *self.text.get(self.pos)?;
+            self.pos += 1;
+            match byte {
+                b'"' => return String::from_utf8(bytes).ok(),
+                b'\\' => {
+                    let escaped = *self.text.get(self.pos)?;
+                    self.pos += 1;
+                    let ch = match escaped {
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            let hex =
+                                std::str::from_utf8(self.text.get(self.pos..self.pos + 4)?).ok()?;
+                            self.pos += 4;
+                            char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        other => other as char,
+                    };
+                    bytes.extend(ch.to_string().as_bytes());
+                }
+                _ => bytes.push(byte),
+            }
+        }
+    }
+}
+
+/// Elements become objects: attributes as `@name`, repeated children as arrays, text as `#text`
+fn parse_xml(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if !text.starts_with('<') || !text.ends_with('>') {
+        return None;
+    }
+
+    let mut parser = Xml { text, pos: 0 };
+    parser.skip_misc();
+    let (name, value) = parser.element()?;
+    parser.skip_misc();
+    (parser.pos == text.len()).then(|| Value::Object(vec![(name, value)]))
+}
+
+struct Xml<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Xml<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips the prolog, comments and the doctype, returns whether anything was skipped.
+    /// One that isn't closed is left where it is
+    fn skip_misc(&mut self) -> bool {
+        let start = self.pos;
+        loop {
+            self.whitespace();
+            let end = if self.rest().starts_with("<?") {
+                self.rest().find("?>").map(|i| i + 2)
+            } else if self.rest().starts_with("<!--") {
+                self.rest().find("-->").map(|i| i + 3)
+            } else if self.rest().starts_with("<!") {
+                self.rest().find('>').map(|i| i + 1)
+            } else {
+                None
+            };
+            match end {
+                Some(end) => self.pos += end,
+                None => return self.pos > start,
+            }
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        let len = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || "/>=".contains(c))?;
+        if len == 0 {
+            return None;
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Some(name)
+    }
+
+    fn element(&mut self) -> Option<(String, Value)> {
+        self.pos += 1; // <
+        let name = self.name()?;
+        let mut entries: Vec<(String, Value)> = Vec::new();
+
+        loop {
+            self.whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Some((name, element_value(entries, String::new())));
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+
+            let attribute = self.name()?;
+            self.whitespace();
+            self.rest().starts_with('=').then_some(())?;
+            self.pos += 1;
+            self.whitespace();
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')?;
+            let end = self.rest()[1..].find(quote)?;
+            let value = unescape(&self.rest()[1..end + 1]);
+            self.pos += end + 2;
+            entries.push((format!("@{attribute}"), Value::String(value)));
+        }
+
+        let mut text = String::new();
+        loop {
+            if self.rest().starts_with("</") {
+                self.pos += 2;
+                (self.name()? == name).then_some(())?;
+                self.whitespace();
+                self.rest().starts_with('>').then_some(())?;
+                self.pos += 1;
+                return Some((name, element_value(entries, text.trim().to_string())));
+            }
+            if self.rest().starts_with("<!--") || self.rest().starts_with("<?") {
+                // a comment that isn't closed would be found again and again
+                self.skip_misc().then_some(())?;
+                continue;
+            }
+            if self.rest().starts_with("<![CDATA[") {
+                let end = self.rest().find("]]>")?;
+                text.push_str(&self.rest()[9..end]);
+                self.pos += end + 3;
+                continue;
+            }
+            if self.rest().starts_with('<') {
+                let (child, value) = self.element()?;
+                add_child(&mut entries, child, value);
+                continue;
+            }
+
+            let end = self.rest().find('<')?;
+            text.push_str(&unescape(&self.rest()[..end]));
+            self.pos += end;
+        }
+    }
+}
+
+fn add_child(entries: &mut Vec<(String, Value)>, name: String, value: Value) {
+    match entries.iter_mut().find(|(key, _)| *key == name) {
+        Some((_, Value::Array(items))) => items.push(value),
+        Some((_, existing)) => {
+            let first = std::mem::replace(existing, Value::Null);
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => entries.push((name, value)),
+    }
+}
+
+fn element_value(mut entries: Vec<(String, Value)>, text: String) -> Value {
+    if entries.is_empty() {
+        return if text.is_empty() {
+            Value::Null
+        } else {
+            Value::String(text)
+        };
+    }
+    if !text.is_empty() {
+        entries.push(("#text".to_string(), Value::String(text)));
+    }
+    Value::Object(entries)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Block style YAML: nested mappings and lists of scalars, flow collections as JSON
+fn parse_yaml(text: &str) -> Option<Value> {
+    let mut lines: Vec<(usize, String)> = text
+        .lines()
+        .filter(|line| {
+            let trimmed = line.trim();
+            !trimmed.is_empty() && !trimmed.starts_with('#') && trimmed != "---"
+        })
+        .map(|line| {
+            let content = line.trim_start();
+            (
+                line.len() - content.len(),
+                strip_comment(content).to_string(),
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut index = 0;
+    let indent = lines[0].0;
+    let value = yaml_block(&mut lines, &mut index, indent)?;
+    (index == lines.len()).then_some(value)
+}
+
+fn yaml_block(lines: &mut Vec<(usize, String)>, index: &mut usize, indent: usize) -> Option<Value> {
+    let is_item = |line: &str| line == "-" || line.starts_with("- ");
+
+    if is_item(&lines[*index].1) {
+        let mut items = Vec::new();
+        while *index < lines.len() && lines[*index].0 == indent && is_item(&lines[*index].1) {
+            let rest = lines[*index].1[1..].trim_start().to_string();
+            if rest.is_empty() {
+                *index += 1;
+                let child = lines.get(*index).filter(|(i, _)| *i > indent)?.0;
+                items.push(yaml_block(lines, index, child)?);
+            } else if yaml_key(&rest).is_some() {
+                // `- key: value` starts a mapping indented where the key is
+                let child = indent + lines[*index].1.len() - rest.len();
+                lines[*index] = (child, rest);
+                items.push(yaml_block(lines, index, child)?);
+            } else {
+                items.push(yaml_scalar(&rest));
+                *index += 1;
+            }
+        }
+        return Some(Value::Array(items));
+    }
+
+    let mut entries = Vec::new();
+    while *index < lines.len() && lines[*index].0 == indent {
+        let (key, value) = yaml_key(&lines[*index].1)?;
+        *index += 1;
+
+        let value = if value.is_empty() {
+            // a list under a key may sit at the key's own indent
+            match lines.get(*index) {
+                Some((child, line)) if *child > indent || (*child == indent && is_item(line)) => {
+                    let child = *child;
+                    yaml_block(lines, index, child)?
+                }
+                _ => Value::Null,
+            }
+        } else {
+            yaml_scalar(&value)
+        };
+        entries.push((key, value));
+    }
+    (!entries.is_empty()).then_some(Value::Object(entries))
+}
+
+/// Splits `key: value`, the value may be empty
+fn yaml_key(line: &str) -> Option<(String, String)> {
+    let (key, value) = match line.strip_suffix(':') {
+        Some(key) if !key.contains(": ") => (key, ""),
+        _ => line.split_once(": ")?,
+    };
+    let key = key.trim().trim_matches(|c| c == '"' || c == '\'');
+    (!key.is_empty()).then(|| (key.to_string(), value.trim().to_string()))
+}
+
+fn yaml_scalar(text: &str) -> Value {
+    let quoted = text.len() >= 2
+        && ((text.starts_with('"') && text.ends_with('"'))
+            || (text.starts_with('\'') && text.ends_with('\'')));
+    if quoted {
+        return Value::String(text[1..text.len() - 1].to_string());
+    }
+
+    match text {
+        "null" | "~" => Value::Null,
+        "true" | "yes" | "on" => Value::Bool(true),
+        "false" | "no" | "off" => Value::Bool(false),
+        _ if text.parse::<f64>().is_ok() => Value::Number(text.to_string()),
+        _ if text.starts_with(['[', '{']) => {
+            parse_json(text).unwrap_or_else(|| Value::String(text.to_string()))
+        }
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// Cuts a `# comment` off, unless it's inside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (i, ch) in line.char_indices() {
+        match (ch, quote) {
+            ('"' | '\'', None) => quote = Some(ch),
+            (c, Some(q)) if c == q => quote = None,
+            ('#', None) if i == 0 || line[..i].ends_with(' ') => return line[..i].trim_end(),
+            _ => {}
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(value: &Value) -> Vec<&str> {
+        match value {
+            Value::Object(entries) => entries.iter().map(|(key, _)| key.as_str()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_json() {
+        let value = parse_json(r#"{"a": [1, true, null], "b": {"c": "d\"e"}}"#).unwrap();
+        assert_eq!(keys(&value), ["a", "b"]);
+        let Value::Object(entries) = &value else {
+            unreachable!()
+        };
+        assert!(matches!(&entries[0].1, Value::Array(items) if items.len() == 3));
+        assert!(
+            matches!(&entries[1].1, Value::Object(c) if matches!(&c[0].1, Value::String(s) if s == "d\"e"))
+        );
+
+        assert!(parse_json("{\"a\": 1,}").is_none());
+        assert!(parse_json("[1, 2] trailing").is_none());
+    }
+
+    #[test]
+    fn parses_yaml() {
+        let value =
+            parse_yaml("name: app\nports:\n  - 80\n  - 443\nenv:\n  debug: true # on\n").unwrap();
+        assert_eq!(keys(&value), ["name", "ports", "env"]);
+        assert!(parse_yaml("just some words").is_none());
+    }
+
+    #[test]
+    fn parses_xml() {
+        let value =
+            parse_xml(r#"<?xml version="1.0"?><a x="1"><b>one</b><b>two</b><!-- c --></a>"#)
+                .unwrap();
+        let Value::Object(root) = &value else {
+            unreachable!()
+        };
+        assert_eq!(keys(&root[0].1), ["@x", "b"]);
+        assert!(parse_xml("<a><b></a>").is_none());
+    }
+
+    #[test]
+    fn unclosed_xml_comment_is_not_parsed() {
+        assert!(parse_xml("<p><!-- TODO</p>").is_none());
+        assert!(parse_xml("<p><?php echo 1;</p>").is_none());
+        assert!(!Tree::detect("<p><!-- TODO</p>"));
+    }
+
+    #[test]
+    fn detects_json_and_xml_only() {
+        assert!(Tree::detect("{\"a\": 1}"));
+        assert!(Tree::detect("<a><b/></a>"));
+        assert!(!Tree::detect("\"just a string\""));
+        assert!(!Tree::detect("name: app\nversion: 1"));
+    }
+
+    #[test]
+    fn folding_keeps_the_hovered_row() {
+        let mut tree = Tree::parse("{\"a\": {\"b\": 1, \"c\": 2}}").unwrap();
+        assert_eq!(tree.rows.len(), 6);
+
+        tree.hover(Some(5));
+        // the branch of `a` folds, leaving three rows
+        assert_eq!(tree.click(1), None);
+        assert_eq!(tree.rows.len(), 3);
+        assert_eq!(tree.hovered, None);
+
+        assert_eq!(tree.click(1), None);
+        assert_eq!(tree.rows.len(), 6);
+        assert_eq!(tree.click(2).as_deref(), Some("1"));
+    }
+}
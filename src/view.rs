@@ -0,0 +1,78 @@
+use crate::ansi;
+use crate::checklist::Checklist;
+use crate::code::Language;
+use crate::diff;
+use crate::swatch::Swatch;
+use crate::table::Table;
+use crate::tree::Tree;
+
+/// How a text note is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextView {
+    Text,
+    Checklist,
+    Markdown,
+    Code,
+    Diff,
+    SideBySide,
+    Tree,
+    Table,
+    Terminal,
+    Swatch,
+}
+
+impl TextView {
+    pub const ALL: [TextView; 10] = [
+        TextView::Text,
+        TextView::Checklist,
+        TextView::Markdown,
+        TextView::Code,
+        TextView::Diff,
+        TextView::SideBySide,
+        TextView::Tree,
+        TextView::Table,
+        TextView::Terminal,
+        TextView::Swatch,
+    ];
+
+    /// The view a text opens with
+    pub fn detect(text: &str) -> Self {
+        if ansi::has_escapes(text) {
+            TextView::Terminal
+        } else if Swatch::detect(text) {
+            TextView::Swatch
+        } else if diff::is_diff(text) {
+            TextView::Diff
+        } else if Checklist::is_task_list(text) {
+            TextView::Checklist
+        } else if Tree::detect(text) {
+            TextView::Tree
+        } else if Table::detect(text) {
+            TextView::Table
+        } else if Language::detect(text).is_some() {
+            TextView::Code
+        } else {
+            TextView::Text
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TextView::Text => "Text",
+            TextView::Checklist => "Checklist",
+            TextView::Markdown => "Markdown",
+            TextView::Code => "Code",
+            TextView::Diff => "Diff",
+            TextView::SideBySide => "Side by side",
+            TextView::Tree => "Tree",
+            TextView::Table => "Table",
+            TextView::Terminal => "Terminal",
+            TextView::Swatch => "Color",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = TextView::ALL.iter().position(|view| *view == self).unwrap();
+        TextView::ALL[(index + 1) % TextView::ALL.len()]
+    }
+}